@@ -13,5 +13,44 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, criterion_benchmark);
+pub fn text_benchmark(c: &mut Criterion) {
+    let input = "int foo = bar(\"baz\", 1.0); // qux\n".repeat(10_000);
+    let mut group = c.benchmark_group("text_10k_lines");
+    group.bench_function("text", |b| {
+        b.iter(|| {
+            let _res: Vec<_> = black_box(
+                SourcepawnLexer::new(&input)
+                    .map(|symbol| symbol.text())
+                    .collect(),
+            );
+        })
+    });
+    group.bench_function("current_text", |b| {
+        b.iter(|| {
+            let mut lexer = SourcepawnLexer::new(&input);
+            let mut res = Vec::new();
+            while lexer.next().is_some() {
+                res.push(lexer.current_text());
+            }
+            black_box(res);
+        })
+    });
+    group.finish();
+}
+
+pub fn literal_benchmark(c: &mut Criterion) {
+    let input = "#pragma deprecated foo \\\nbar\nchar s[] = \"baz\"; /* qux\n */\n".repeat(10_000);
+    c.bench_function("literals_10k_lines", |b| {
+        b.iter(|| {
+            let _res: Vec<_> = black_box(SourcepawnLexer::new(&input).collect());
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    criterion_benchmark,
+    text_benchmark,
+    literal_benchmark
+);
 criterion_main!(benches);
@@ -0,0 +1,326 @@
+use crate::{SourcepawnLexer, Symbol, TextSize, TokenKind};
+
+/// A symbol tagged as [trivia](TokenKind::is_trivia) or as a significant token, see
+/// [`SourcepawnLexer::pieces`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Piece {
+    /// Whitespace, a newline, a line continuation, a comment or a shebang.
+    Trivia(Symbol),
+
+    /// Any other symbol, including the [Eof](TokenKind::Eof) token.
+    Token(Symbol),
+}
+
+impl Piece {
+    /// Tags a symbol based on [`TokenKind::is_trivia`].
+    pub fn new(symbol: Symbol) -> Self {
+        if symbol.token_kind.is_trivia() {
+            Self::Trivia(symbol)
+        } else {
+            Self::Token(symbol)
+        }
+    }
+
+    /// Returns whether the piece is trivia.
+    pub fn is_trivia(&self) -> bool {
+        matches!(self, Self::Trivia(_))
+    }
+
+    /// Returns the tagged symbol.
+    pub fn symbol(&self) -> &Symbol {
+        match self {
+            Self::Trivia(symbol) | Self::Token(symbol) => symbol,
+        }
+    }
+
+    /// Consumes the piece and returns the tagged symbol.
+    pub fn into_symbol(self) -> Symbol {
+        match self {
+            Self::Trivia(symbol) | Self::Token(symbol) => symbol,
+        }
+    }
+}
+
+impl<'a> SourcepawnLexer<'a> {
+    /// Consumes the lexer and returns an iterator which ends before the [Eof](TokenKind::Eof) token,
+    /// instead of yielding it as the last symbol.
+    ///
+    /// The state of the lexer, such as [in_preprocessor](SourcepawnLexer::in_preprocessor), is updated the same way
+    /// as when iterating over the lexer directly.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{SourcepawnLexer, TokenKind};
+    ///
+    /// let lexer = SourcepawnLexer::new("int foo;");
+    /// let kinds: Vec<_> = lexer.without_eof().map(|symbol| symbol.token_kind).collect();
+    /// assert_eq!(kinds.last(), Some(&TokenKind::Semicolon));
+    /// ```
+    pub fn without_eof(self) -> impl Iterator<Item = Symbol> + 'a {
+        self.take_while(|symbol| symbol.token_kind != TokenKind::Eof)
+    }
+
+    /// Consumes the lexer and returns the symbols which start before the byte offset, such as a
+    /// cursor position, so that they can be walked backwards from the offset.
+    ///
+    /// The last symbol can contain the offset. Lexing stops at the first symbol which starts at or
+    /// after the offset, so the rest of the input is not lexed, and the [Eof](TokenKind::Eof) token
+    /// is never returned. The symbols are the same as when lexing the whole input, deltas included.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let input = "int foo = bar;";
+    /// let symbols = SourcepawnLexer::new(input).tokens_before(9.into());
+    /// let previous: Vec<_> = symbols.iter().rev().map(|symbol| symbol.text()).collect();
+    /// assert_eq!(previous, vec!["=", "foo", "int"]);
+    /// ```
+    pub fn tokens_before(self, offset: TextSize) -> Vec<Symbol> {
+        self.take_while(|symbol| {
+            symbol.range.start() < offset && symbol.token_kind != TokenKind::Eof
+        })
+        .collect()
+    }
+
+    /// Consumes the lexer and returns an iterator which tags each symbol as [trivia](Piece::Trivia)
+    /// or as a [significant token](Piece::Token).
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{Piece, SourcepawnLexer};
+    ///
+    /// let lexer = SourcepawnLexer::new("foo // bar");
+    /// let pieces: Vec<_> = lexer.pieces().collect();
+    /// assert!(matches!(pieces[0], Piece::Token(_)));
+    /// assert!(matches!(pieces[1], Piece::Trivia(_)));
+    /// ```
+    pub fn pieces(self) -> impl Iterator<Item = Piece> + 'a {
+        self.map(Piece::new)
+    }
+
+    /// Consumes the lexer and returns an iterator which drops the
+    /// [LineContinuation](TokenKind::LineContinuation) tokens, as if they were whitespace.
+    ///
+    /// The width of a dropped line continuation is added to the [delta](Symbol::delta) of the
+    /// next token, so that deltas remain relative to the end of the previous yielded token.
+    /// Deltas computed with
+    /// [`LexerBuilder::line_relative_deltas`](crate::LexerBuilder::line_relative_deltas) are not
+    /// supported.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{Literal, SourcepawnLexer, TokenKind};
+    ///
+    /// let lexer = SourcepawnLexer::new("#define FOO \\\n1");
+    /// let symbols: Vec<_> = lexer.join_continuations().collect();
+    /// assert_eq!(symbols[2].token_kind, TokenKind::Literal(Literal::IntegerLiteral));
+    /// assert_eq!(symbols[2].delta, 3);
+    /// ```
+    pub fn join_continuations(self) -> impl Iterator<Item = Symbol> + 'a {
+        self.drop_folding_delta(|symbol| symbol.token_kind == TokenKind::LineContinuation)
+    }
+
+    /// Consumes the lexer and returns an iterator which applies `f` to the significant tokens, i.e
+    /// the symbols which are not [trivia](TokenKind::is_trivia), and yields its results.
+    ///
+    /// The iterator stops at the first [`None`] returned by `f`, or before the
+    /// [Eof](TokenKind::Eof) token, which is never passed to `f`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let lexer = SourcepawnLexer::new("foo /* bar */ baz; qux");
+    /// let names: Vec<_> = lexer
+    ///     .map_while_significant(|symbol| symbol.as_identifier().map(str::to_string))
+    ///     .collect();
+    /// assert_eq!(names, vec!["foo", "baz"]);
+    /// ```
+    pub fn map_while_significant<T, F>(self, f: F) -> impl Iterator<Item = T> + 'a
+    where
+        F: FnMut(Symbol) -> Option<T> + 'a,
+    {
+        self.filter(|symbol| !symbol.token_kind.is_trivia())
+            .take_while(|symbol| symbol.token_kind != TokenKind::Eof)
+            .map_while(f)
+    }
+
+    /// Consumes the lexer and returns an iterator which drops the [trivia](Symbol::is_trivia).
+    ///
+    /// The width of the dropped symbols and their deltas are added to the [delta](Symbol::delta)
+    /// of the next token, so that deltas remain relative to the end of the previous yielded token.
+    /// Deltas computed with
+    /// [`LexerBuilder::line_relative_deltas`](crate::LexerBuilder::line_relative_deltas) are not
+    /// supported.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let lexer = SourcepawnLexer::new("int /* foo */ bar;");
+    /// let symbols: Vec<_> = lexer.without_trivia().collect();
+    /// assert_eq!(symbols[1].text(), "bar");
+    /// assert_eq!(symbols[1].delta, 11);
+    /// ```
+    pub fn without_trivia(self) -> impl Iterator<Item = Symbol> + 'a {
+        self.drop_folding_delta(Symbol::is_trivia)
+    }
+
+    /// Drops the symbols matching `pred`, and adds their widths and deltas to the delta of the
+    /// next yielded symbol.
+    fn drop_folding_delta<P>(self, mut pred: P) -> impl Iterator<Item = Symbol> + 'a
+    where
+        P: FnMut(&Symbol) -> bool + 'a,
+    {
+        let mut pending_delta = 0;
+        self.filter_map(move |mut symbol| {
+            if pred(&symbol) {
+                pending_delta += symbol.delta + u32::from(symbol.range.len()) as i32;
+                return None;
+            }
+            symbol.delta += std::mem::take(&mut pending_delta);
+            Some(symbol)
+        })
+    }
+
+    /// Consumes the lexer and returns an iterator which pairs each symbol with the text of the gap
+    /// before it, i.e the whitespace between the end of the previous symbol and its start.
+    ///
+    /// `source` must be the input of the lexer. The gap is [`None`] when the symbol starts right
+    /// after the previous one. The gap before the [Eof](TokenKind::Eof) token is the trailing
+    /// whitespace of the input, so the source can be rebuilt from the gaps and the symbols without
+    /// emitting [whitespace](crate::LexerBuilder::emit_whitespace) tokens.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let input = "int  foo;";
+    /// let gaps: Vec<_> = SourcepawnLexer::new(input)
+    ///     .with_gaps(input)
+    ///     .map(|(gap, _)| gap)
+    ///     .collect();
+    /// assert_eq!(gaps, vec![None, Some("  "), None, None]);
+    /// ```
+    pub fn with_gaps(
+        self,
+        source: &'a str,
+    ) -> impl Iterator<Item = (Option<&'a str>, Symbol)> + 'a {
+        let mut prev_end = TextSize::from(0);
+        self.map(move |symbol| {
+            let start = symbol.range.start();
+            let gap = if start > prev_end {
+                source.get(usize::from(prev_end)..usize::from(start))
+            } else {
+                None
+            };
+            prev_end = symbol.range.end();
+            (gap, symbol)
+        })
+    }
+
+    /// Consumes the lexer and returns an iterator which yields each symbol with its nesting depth
+    /// in parentheses, brackets and braces.
+    ///
+    /// An opening `(`, `[` or `{` is at the depth of the enclosing tokens, and the tokens after it
+    /// are one level deeper. A closing `)`, `]` or `}` is back at the depth of the enclosing tokens.
+    /// The kinds of the delimiters are not matched, and a closing delimiter at depth 0 is ignored.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let lexer = SourcepawnLexer::new("f(a)");
+    /// let depths: Vec<_> = lexer.with_depth().map(|(_, depth)| depth).collect();
+    /// assert_eq!(depths, vec![0, 0, 1, 0, 0]);
+    /// ```
+    pub fn with_depth(self) -> impl Iterator<Item = (Symbol, u32)> + 'a {
+        let mut depth = 0u32;
+        self.map(move |symbol| match symbol.token_kind {
+            TokenKind::LParen | TokenKind::LBracket | TokenKind::LBrace => {
+                depth += 1;
+                (symbol, depth - 1)
+            }
+            TokenKind::RParen | TokenKind::RBracket | TokenKind::RBrace => {
+                depth = depth.saturating_sub(1);
+                (symbol, depth)
+            }
+            _ => (symbol, depth),
+        })
+    }
+
+    /// Consumes the lexer and returns an iterator over the [comments](TokenKind::Comment) only.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let lexer = SourcepawnLexer::new("int foo; // bar\n/* baz */");
+    /// let comments: Vec<_> = lexer.comments().map(|symbol| symbol.text()).collect();
+    /// assert_eq!(comments, vec!["// bar", "/* baz */"]);
+    /// ```
+    pub fn comments(self) -> impl Iterator<Item = Symbol> + 'a {
+        self.filter(|symbol| matches!(symbol.token_kind, TokenKind::Comment(_)))
+    }
+
+    /// Consumes the lexer and returns an iterator which pairs each
+    /// [documentation comment](Symbol::is_doc_comment) with the next significant token, i.e the
+    /// next symbol which is not [trivia](TokenKind::is_trivia).
+    ///
+    /// Consecutive documentation comments are all paired with the same token. A documentation
+    /// comment which is not followed by any significant token is paired with the
+    /// [Eof](TokenKind::Eof) token.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{SourcepawnLexer, TokenKind};
+    ///
+    /// let lexer = SourcepawnLexer::new("/// Does foo.\nnative void Foo();");
+    /// let pairs: Vec<_> = lexer.doc_comments().collect();
+    /// assert_eq!(pairs[0].0.text(), "/// Does foo.");
+    /// assert_eq!(pairs[0].1.token_kind, TokenKind::Native);
+    /// ```
+    pub fn doc_comments(self) -> impl Iterator<Item = (Symbol, Symbol)> + 'a {
+        let mut pending = Vec::new();
+        self.flat_map(move |symbol| {
+            if symbol.is_doc_comment() {
+                pending.push(symbol);
+                return Vec::new();
+            }
+            if symbol.token_kind.is_trivia() {
+                return Vec::new();
+            }
+            std::mem::take(&mut pending)
+                .into_iter()
+                .map(|comment| (comment, symbol.clone()))
+                .collect()
+        })
+    }
+}
+
+/// Iterates over a clone of the lexer, from its current position, without consuming it.
+///
+/// Each loop clones the whole lexer state, including the macros collected by
+/// [`LexerBuilder::track_defines`](crate::LexerBuilder::track_defines). The input itself is
+/// borrowed and is not copied.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::SourcepawnLexer;
+///
+/// let lexer = SourcepawnLexer::new("int foo;");
+/// let mut count = 0;
+/// for _ in &lexer {
+///     count += 1;
+/// }
+/// assert_eq!(count, lexer.count());
+/// ```
+impl<'a> IntoIterator for &SourcepawnLexer<'a> {
+    type Item = Symbol;
+    type IntoIter = SourcepawnLexer<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.clone()
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{Checkpoint, SourcepawnLexer, TokenKind};
+
+/// Default value of [`LexerBuilder::max_token_len`], 1 MiB.
+const DEFAULT_MAX_TOKEN_LEN: usize = 1 << 20;
+
+/// Default value of [`LexerBuilder::look_behind`].
+const DEFAULT_LOOK_BEHIND: usize = 2;
+
+/// Version of the Sourcepawn syntax to lex.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    /// The current syntax, with all the keywords.
+    #[default]
+    Modern,
+
+    /// The syntax before the introduction of methodmaps and new declarations.
+    ///
+    /// `methodmap`, `property`, `typedef`, `typeset`, `view_as`, `null`, `this` and `delete` are
+    /// lexed as identifiers.
+    Legacy,
+}
+
+impl Dialect {
+    /// Returns whether the kind is a [keyword](TokenKind::keyword_text) in this dialect.
+    pub(crate) fn has_keyword(self, kind: TokenKind) -> bool {
+        if kind.keyword_text().is_none() {
+            return false;
+        }
+        match self {
+            Self::Modern => true,
+            Self::Legacy => !matches!(
+                kind,
+                TokenKind::Methodmap
+                    | TokenKind::Property
+                    | TokenKind::Typedef
+                    | TokenKind::Typeset
+                    | TokenKind::ViewAs
+                    | TokenKind::Null
+                    | TokenKind::This
+                    | TokenKind::Delete
+            ),
+        }
+    }
+}
+
+/// Builder for a configured [`SourcepawnLexer`].
+///
+/// [`SourcepawnLexer::new`] is equivalent to building a lexer with the default configuration.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{Dialect, LexerBuilder};
+///
+/// let lexer = LexerBuilder::new()
+///     .emit_whitespace(true)
+///     .dialect(Dialect::Modern)
+///     .build("int foo = 0;");
+/// ```
+#[derive(Debug, Clone)]
+pub struct LexerBuilder {
+    pub(crate) max_token_len: usize,
+    pub(crate) line_relative_deltas: bool,
+    pub(crate) allow_shebang: bool,
+    pub(crate) emit_whitespace: bool,
+    pub(crate) dialect: Dialect,
+    pub(crate) normalize_newlines: bool,
+    pub(crate) track_defines: bool,
+    pub(crate) attribute_syntax: bool,
+    pub(crate) look_behind: usize,
+}
+
+impl Default for LexerBuilder {
+    fn default() -> Self {
+        Self {
+            max_token_len: DEFAULT_MAX_TOKEN_LEN,
+            line_relative_deltas: false,
+            allow_shebang: false,
+            emit_whitespace: false,
+            dialect: Dialect::default(),
+            normalize_newlines: true,
+            track_defines: false,
+            attribute_syntax: false,
+            look_behind: DEFAULT_LOOK_BEHIND,
+        }
+    }
+}
+
+impl LexerBuilder {
+    /// Creates a new builder with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum length in bytes that the lexer will scan for a single block comment or
+    /// preprocessor directive (including its line continuations). Defaults to 1 MiB.
+    ///
+    /// A token which exceeds the limit is cut and emitted as a
+    /// [`TokenKind::Error(LexError::TokenTooLong)`](crate::LexError::TokenTooLong) token, which
+    /// prevents untrusted inputs from making the lexer scan the same text over and over.
+    pub fn max_token_len(mut self, max_token_len: usize) -> Self {
+        self.max_token_len = max_token_len;
+        self
+    }
+
+    /// Makes the [delta](crate::Delta) of the first token of each line relative to the start of
+    /// that line instead of the end of the previous token. Defaults to `false`.
+    ///
+    /// A token is the first of its line if no previous token ends on that line. Its delta is then
+    /// the byte offset of the token from the start of the line, i.e its column. This includes the
+    /// first token of the input, whose delta is otherwise always 0. The delta of any other token
+    /// is still relative to the end of the previous token.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::LexerBuilder;
+    ///
+    /// let mut lexer = LexerBuilder::new().line_relative_deltas(true).build("    int foo;");
+    /// assert_eq!(lexer.next().unwrap().delta, 4);
+    /// ```
+    pub fn line_relative_deltas(mut self, line_relative_deltas: bool) -> Self {
+        self.line_relative_deltas = line_relative_deltas;
+        self
+    }
+
+    /// Lexes a first line which starts with `#!` as a single [`TokenKind::Shebang`](crate::TokenKind::Shebang)
+    /// token, instead of `#` and `!` tokens. Defaults to `false`.
+    ///
+    /// The token stops before the line break.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{LexerBuilder, TokenKind};
+    ///
+    /// let mut lexer = LexerBuilder::new().allow_shebang(true).build("#!/usr/bin/spcomp\nint x;");
+    /// assert_eq!(lexer.next().unwrap().token_kind, TokenKind::Shebang);
+    /// ```
+    pub fn allow_shebang(mut self, allow_shebang: bool) -> Self {
+        self.allow_shebang = allow_shebang;
+        self
+    }
+
+    /// Emits spaces and tabs as [`TokenKind::Whitespace`](crate::TokenKind::Whitespace) tokens
+    /// instead of skipping them. Defaults to `false`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{LexerBuilder, TokenKind};
+    ///
+    /// let mut lexer = LexerBuilder::new().emit_whitespace(true).build("int  foo;");
+    /// assert_eq!(lexer.nth(1).unwrap().token_kind, TokenKind::Whitespace);
+    /// ```
+    pub fn emit_whitespace(mut self, emit_whitespace: bool) -> Self {
+        self.emit_whitespace = emit_whitespace;
+        self
+    }
+
+    /// Sets the [`Dialect`] to lex. Defaults to [`Dialect::Modern`].
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Reports the text of every [`TokenKind::Newline`](crate::TokenKind::Newline) token as `\n`,
+    /// whether it is `\n` or `\r\n` in the input, and the line ending of every
+    /// [`TokenKind::LineContinuation`](crate::TokenKind::LineContinuation) token as `\n`.
+    /// Defaults to `true`.
+    ///
+    /// When disabled, [`Symbol::text`](crate::Symbol::text) returns the exact line ending, so that
+    /// the input can be rebuilt from the symbols. The [range](crate::Symbol::range) of a newline or
+    /// a line continuation always covers its exact text.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::LexerBuilder;
+    ///
+    /// let mut lexer = LexerBuilder::new().normalize_newlines(false).build("\r\n");
+    /// assert_eq!(lexer.next().unwrap().text(), "\r\n");
+    /// ```
+    pub fn normalize_newlines(mut self, normalize_newlines: bool) -> Self {
+        self.normalize_newlines = normalize_newlines;
+        self
+    }
+
+    /// Collects the macros defined with `#define` into [`SourcepawnLexer::defines`], and reports a
+    /// [`LexError::RedefinedMacro`](crate::LexError::RedefinedMacro) diagnostic in
+    /// [`SourcepawnLexer::diagnostics`] when a macro is defined again with a different value.
+    /// Defaults to `false`.
+    ///
+    /// This is not a preprocessor: conditional directives such as `#if` are ignored.
+    pub fn track_defines(mut self, track_defines: bool) -> Self {
+        self.track_defines = track_defines;
+        self
+    }
+
+    /// Lexes `[[` followed by the first `]]` of the same line as a single
+    /// [`TokenKind::Attribute`](crate::TokenKind::Attribute) token, for tools experimenting with
+    /// annotations such as `[[deprecated]]`. Defaults to `false`.
+    ///
+    /// Sourcepawn has no attributes, so this is disabled by default and `[[` is lexed as two
+    /// brackets. A `[[` which is not closed on its line is also lexed as two brackets.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{LexerBuilder, TokenKind};
+    ///
+    /// let mut lexer = LexerBuilder::new().attribute_syntax(true).build("[[deprecated]] int x;");
+    /// assert_eq!(lexer.next().unwrap().token_kind, TokenKind::Attribute);
+    /// ```
+    pub fn attribute_syntax(mut self, attribute_syntax: bool) -> Self {
+        self.attribute_syntax = attribute_syntax;
+        self
+    }
+
+    /// Sets the number of significant symbols, i.e which are not [trivia](crate::TokenKind::is_trivia),
+    /// that the lexer remembers, see [`SourcepawnLexer::recent_significant`]. Defaults to 2.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::LexerBuilder;
+    ///
+    /// let mut lexer = LexerBuilder::new().look_behind(3).build("int foo;");
+    /// lexer.nth(2);
+    /// assert_eq!(lexer.recent_significant(3).len(), 3);
+    /// ```
+    pub fn look_behind(mut self, look_behind: usize) -> Self {
+        self.look_behind = look_behind;
+        self
+    }
+
+    /// Builds a [`SourcepawnLexer`] for the given input.
+    pub fn build(self, input: &str) -> SourcepawnLexer<'_> {
+        SourcepawnLexer::with_config(input, self)
+    }
+
+    /// Builds a [`SourcepawnLexer`] which resumes lexing the input at a checkpoint, see
+    /// [`SourcepawnLexer::from_checkpoint`].
+    pub fn build_from_checkpoint(self, input: &str, checkpoint: Checkpoint) -> SourcepawnLexer<'_> {
+        SourcepawnLexer::with_checkpoint(input, self, checkpoint)
+    }
+}
@@ -0,0 +1,166 @@
+use std::{iter::FusedIterator, ops::Range};
+
+use text_size::{TextRange, TextSize};
+
+use crate::{Checkpoint, Delta, LexError, SourcepawnLexer, Symbol, TokenKind};
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+const UTF16_LE_BOM: &[u8] = b"\xFF\xFE";
+const UTF16_BE_BOM: &[u8] = b"\xFE\xFF";
+
+/// Lexer over raw bytes which may not be valid UTF-8, created with [`SourcepawnLexer::from_bytes`].
+///
+/// The valid UTF-8 spans of the input are lexed with a [`SourcepawnLexer`] each, and every invalid
+/// byte sequence is emitted as a [`TokenKind::Error(LexError::InvalidUtf8)`](LexError::InvalidUtf8)
+/// token. Ranges and deltas are relative to the original bytes.
+///
+/// Since lexing restarts after an invalid byte sequence, a token which contains one, such as a
+/// string literal or a comment, is split around it. The lexer of the next span resumes at the
+/// [checkpoint](Checkpoint) of the previous one, so the preprocessor state, the
+/// [control character](SourcepawnLexer::ctrl_char) and the
+/// [rational tag](SourcepawnLexer::rational_tag) carry over the invalid bytes.
+#[derive(Debug, Clone)]
+pub struct ByteLexer<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    lexer: Option<SourcepawnLexer<'a>>,
+    in_preprocessor: bool,
+    checkpoint: Checkpoint,
+    chunk_start: u32,
+    invalid: Option<Range<usize>>,
+    prev_end: Option<TextSize>,
+    eof: bool,
+}
+
+impl<'a> SourcepawnLexer<'a> {
+    /// Creates a lexer over raw bytes, which emits invalid UTF-8 as error tokens instead of
+    /// shifting the offsets of the following tokens like [`String::from_utf8_lossy`] would.
+    ///
+    /// A leading UTF-8 byte order mark is skipped, and ranges stay relative to the original
+    /// bytes.
+    ///
+    /// # Errors
+    /// Returns [`LexError::UnsupportedEncoding`] if the bytes start with a UTF-16 byte order mark,
+    /// as Sourcepawn files must be encoded in UTF-8.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{LexError, SourcepawnLexer, TokenKind};
+    ///
+    /// let symbols: Vec<_> = SourcepawnLexer::from_bytes(b"int \xFF foo;").unwrap().collect();
+    /// assert_eq!(symbols[1].token_kind, TokenKind::Error(LexError::InvalidUtf8));
+    /// assert_eq!(u32::from(symbols[2].range.start()), 6);
+    /// ```
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<ByteLexer<'a>, LexError> {
+        if bytes.starts_with(UTF16_LE_BOM) || bytes.starts_with(UTF16_BE_BOM) {
+            return Err(LexError::UnsupportedEncoding);
+        }
+        let offset = if bytes.starts_with(UTF8_BOM) {
+            UTF8_BOM.len()
+        } else {
+            0
+        };
+
+        Ok(ByteLexer {
+            bytes,
+            offset,
+            lexer: None,
+            in_preprocessor: false,
+            checkpoint: Checkpoint::default(),
+            chunk_start: 0,
+            invalid: None,
+            prev_end: None,
+            eof: false,
+        })
+    }
+}
+
+impl ByteLexer<'_> {
+    fn delta(&mut self, range: TextRange) -> Delta {
+        let delta = self.prev_end.map_or(Delta::default(), |prev_end| {
+            u32::from(range.start()) as i32 - u32::from(prev_end) as i32
+        });
+        self.prev_end = Some(range.end());
+        delta
+    }
+
+    /// Splits the next valid UTF-8 span and the invalid bytes which follow it off the input.
+    fn next_chunk(&mut self) {
+        let rest = &self.bytes[self.offset..];
+        let (valid, invalid_len) = match std::str::from_utf8(rest) {
+            Ok(valid) => (valid, 0),
+            Err(err) => {
+                let valid_len = err.valid_up_to();
+                let invalid_len = err.error_len().unwrap_or(rest.len() - valid_len);
+                // Safe unwrap as the bytes have just been validated.
+                let valid = std::str::from_utf8(&rest[..valid_len]).unwrap();
+                (valid, invalid_len)
+            }
+        };
+        self.chunk_start = self.offset as u32;
+        self.offset += valid.len();
+        if invalid_len > 0 {
+            self.invalid = Some(self.offset..self.offset + invalid_len);
+            self.offset += invalid_len;
+        }
+        if !valid.is_empty() {
+            let checkpoint = Checkpoint {
+                offset: TextSize::from(0),
+                ..self.checkpoint.clone()
+            };
+            self.lexer = Some(SourcepawnLexer::from_checkpoint(valid, checkpoint));
+        }
+    }
+}
+
+impl Iterator for ByteLexer<'_> {
+    type Item = Symbol;
+
+    fn next(&mut self) -> Option<Symbol> {
+        loop {
+            if let Some(lexer) = &mut self.lexer {
+                match lexer.next() {
+                    Some(mut symbol) if symbol.token_kind != TokenKind::Eof => {
+                        self.in_preprocessor = lexer.in_preprocessor();
+                        symbol.range += TextSize::from(self.chunk_start);
+                        symbol.delta = self.delta(symbol.range);
+                        return Some(symbol);
+                    }
+                    _ => {
+                        // The end of the span is not the end of the input, and does not end a
+                        // preprocessor directive.
+                        self.checkpoint = lexer.checkpoint();
+                        self.checkpoint.in_preprocessor = self.in_preprocessor;
+                        self.lexer = None;
+                    }
+                }
+            }
+            if let Some(invalid) = self.invalid.take() {
+                let range = TextRange::new(
+                    TextSize::from(invalid.start as u32),
+                    TextSize::from(invalid.end as u32),
+                );
+                let mut symbol = Symbol::new(
+                    TokenKind::Error(LexError::InvalidUtf8),
+                    Some(char::REPLACEMENT_CHARACTER.encode_utf8(&mut [0; 4])),
+                    range,
+                    self.delta(range),
+                );
+                symbol.in_preprocessor = self.checkpoint.in_preprocessor;
+                return Some(symbol);
+            }
+            if self.offset < self.bytes.len() {
+                self.next_chunk();
+                continue;
+            }
+            if self.eof {
+                return None;
+            }
+            self.eof = true;
+            let range = TextRange::empty(TextSize::from(self.bytes.len() as u32));
+            return Some(Symbol::new(TokenKind::Eof, None, range, self.delta(range)));
+        }
+    }
+}
+
+impl FusedIterator for ByteLexer<'_> {}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use smol_str::SmolStr;
+use text_size::TextRange;
+
+use crate::{LexError, PreprocDir, Symbol, TokenKind};
+
+#[derive(Debug, Clone, Default)]
+enum State {
+    #[default]
+    Idle,
+    /// After `#define` or `#undef`, waiting for the name of the macro.
+    Name { undef: bool },
+    /// In the value of the macro.
+    Value {
+        name: SmolStr,
+        range: TextRange,
+        value: Vec<SmolStr>,
+    },
+}
+
+/// Collects the macros defined with `#define`, see
+/// [`LexerBuilder::track_defines`](crate::LexerBuilder::track_defines).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DefineTracker {
+    defines: HashMap<SmolStr, SmolStr>,
+    state: State,
+}
+
+impl DefineTracker {
+    pub(crate) fn defines(&self) -> &HashMap<SmolStr, SmolStr> {
+        &self.defines
+    }
+
+    /// Updates the macros with the next symbol. `in_preprocessor` is the state of the lexer after
+    /// that symbol.
+    ///
+    /// Returns a [`LexError::RedefinedMacro`] diagnostic at the name of a macro which is defined
+    /// again with a different value.
+    pub(crate) fn update(
+        &mut self,
+        symbol: &Symbol,
+        in_preprocessor: bool,
+    ) -> Option<(TextRange, LexError)> {
+        if symbol.token_kind.is_trivia() && in_preprocessor {
+            return None;
+        }
+        match &mut self.state {
+            State::Idle => {
+                self.state = match symbol.token_kind {
+                    TokenKind::PreprocDir(PreprocDir::MDefine) => State::Name { undef: false },
+                    TokenKind::PreprocDir(PreprocDir::MUndef) => State::Name { undef: true },
+                    _ => State::Idle,
+                };
+            }
+            State::Name { undef } => {
+                let undef = *undef;
+                self.state = State::Idle;
+                let name = symbol.as_identifier()?;
+                if undef {
+                    self.defines.remove(name);
+                } else if in_preprocessor {
+                    self.state = State::Value {
+                        name: name.into(),
+                        range: symbol.range,
+                        value: Vec::new(),
+                    };
+                } else {
+                    return self.define(name.into(), symbol.range, SmolStr::default());
+                }
+            }
+            State::Value { value, .. } if in_preprocessor => value.push(symbol.text()),
+            State::Value { .. } => {
+                let State::Value { name, range, value } = std::mem::take(&mut self.state) else {
+                    unreachable!();
+                };
+                return self.define(name, range, value.join(" ").into());
+            }
+        }
+
+        None
+    }
+
+    fn define(
+        &mut self,
+        name: SmolStr,
+        range: TextRange,
+        value: SmolStr,
+    ) -> Option<(TextRange, LexError)> {
+        match self.defines.insert(name, value.clone()) {
+            Some(prev_value) if prev_value != value => Some((range, LexError::RedefinedMacro)),
+            _ => None,
+        }
+    }
+}
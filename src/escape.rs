@@ -0,0 +1,103 @@
+use std::{iter::Peekable, ops::Range, str::CharIndices};
+
+/// Default control character, which starts escape sequences in string and char literals.
+pub(crate) const DEFAULT_CTRL_CHAR: char = '\\';
+
+/// Result of decoding an escape sequence.
+pub(crate) enum Escape {
+    /// The escape sequence represents a character.
+    Char(char),
+
+    /// The escape sequence is a line continuation and does not represent anything.
+    LineContinuation,
+}
+
+/// Decodes the escape sequence following a control character, which must already have been
+/// consumed from `chars`. Returns [`None`] if the escape sequence is malformed.
+///
+/// Numeric escape sequences (`\x41`, `\101`) can be terminated by a `;`, which is consumed.
+pub(crate) fn decode_escape(chars: &mut Peekable<CharIndices>, ctrl_char: char) -> Option<Escape> {
+    let (_, ch) = chars.next()?;
+    let decoded = match ch {
+        'a' => '\x07',
+        'b' => '\x08',
+        'e' => '\x1b',
+        'f' => '\x0c',
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        'v' => '\x0b',
+        '\'' | '"' | '%' | '?' | '\\' => ch,
+        '\n' => return Some(Escape::LineContinuation),
+        '\r' => {
+            chars.next_if(|(_, ch)| *ch == '\n')?;
+            return Some(Escape::LineContinuation);
+        }
+        'x' | 'u' => decode_numeric_escape(chars, 16)?,
+        '0'..='7' => {
+            let mut code = ch.to_digit(8)?;
+            while let Some((_, digit)) = chars.next_if(|(_, ch)| ch.is_digit(8)) {
+                code = code.checked_mul(8)?.checked_add(digit.to_digit(8)?)?;
+            }
+            chars.next_if(|(_, ch)| *ch == ';');
+            char::from_u32(code)?
+        }
+        _ if ch == ctrl_char => ch,
+        _ => return None,
+    };
+
+    Some(Escape::Char(decoded))
+}
+
+fn decode_numeric_escape(chars: &mut Peekable<CharIndices>, radix: u32) -> Option<char> {
+    let mut code: Option<u32> = None;
+    while let Some((_, digit)) = chars.next_if(|(_, ch)| ch.is_digit(radix)) {
+        code = Some(
+            code.unwrap_or(0)
+                .checked_mul(radix)?
+                .checked_add(digit.to_digit(radix)?)?,
+        );
+    }
+    chars.next_if(|(_, ch)| *ch == ';');
+    char::from_u32(code?)
+}
+
+/// Decodes the escape sequences of the content of a string or char literal, without its quotes.
+/// Returns [`None`] if one of the escape sequences is malformed.
+pub(crate) fn unescape(text: &str, ctrl_char: char) -> Option<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+    while let Some((_, ch)) = chars.next() {
+        if ch != ctrl_char {
+            out.push(ch);
+            continue;
+        }
+        match decode_escape(&mut chars, ctrl_char)? {
+            Escape::Char(ch) => out.push(ch),
+            Escape::LineContinuation => (),
+        }
+    }
+
+    Some(out)
+}
+
+/// Returns the byte ranges of the malformed escape sequences of the content of a string or char
+/// literal, without its quotes.
+///
+/// A range starts at the control character and ends after the characters that were read to find
+/// that the escape sequence is malformed.
+pub(crate) fn invalid_escapes(text: &str, ctrl_char: char) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, ch)) = chars.next() {
+        if ch != ctrl_char {
+            continue;
+        }
+        if decode_escape(&mut chars, ctrl_char).is_none() {
+            let end = chars.peek().map_or(text.len(), |(end, _)| *end);
+            ranges.push(start..end);
+        }
+    }
+
+    ranges
+}
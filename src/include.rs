@@ -0,0 +1,25 @@
+use smol_str::SmolStr;
+
+/// The delimiters of the path of an `#include` or `#tryinclude` directive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IncludeKind {
+    /// `#include <sourcemod>`
+    Chevron,
+
+    /// `#include "foo.sp"`
+    Quote,
+
+    /// `#include sourcemod`, a path without delimiters.
+    Bare,
+}
+
+/// The path of an `#include` or `#tryinclude` directive and its delimiters, see
+/// [`Symbol::as_include`](crate::Symbol::as_include).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Include {
+    /// Path without its delimiters, verbatim.
+    pub path: SmolStr,
+
+    /// Delimiters of the path.
+    pub kind: IncludeKind,
+}
@@ -1,12 +1,20 @@
-use lazy_static::lazy_static;
 use logos::{Lexer, Logos};
-use regex::Regex;
 use smol_str::SmolStr;
-use text_size::TextRange;
+use text_size::{TextRange, TextSize};
 
-use crate::{token::Token, token_kind::TokenKind, Comment, Literal, PreprocDir};
+use crate::{
+    define::DefineTracker,
+    escape::{invalid_escapes, unescape, DEFAULT_CTRL_CHAR},
+    pragma::{directive_arguments, split_pragma, Pragma},
+    token::{Extras, Token},
+    token_kind::TokenKind,
+    Comment, Include, IncludeKind, LexError, LexerBuilder, LineCol, LineIndex, Literal, Operator,
+    PreprocDir, TokenSet,
+};
 use std::{
+    collections::{HashMap, VecDeque},
     hash::{Hash, Hasher},
+    iter::FusedIterator,
     ops::Range,
 };
 
@@ -34,8 +42,18 @@ pub struct Symbol {
 
     /// Byte delta of the token.
     pub delta: Delta,
+
+    /// Whether or not the token is part of a preprocessing statement, i.e the value of
+    /// [`SourcepawnLexer::in_preprocessor`] right after the token was lexed.
+    ///
+    /// This is not compared by [`PartialEq`] nor hashed, as it is state of the lexer rather than
+    /// of the token.
+    pub in_preprocessor: bool,
 }
 
+/// Hashes the same fields as [`PartialEq`], so that two equal symbols always have the same hash.
+///
+/// The text is hashed through [`Symbol::text`], whether it is stored in the symbol or inferred from its kind.
 impl Hash for Symbol {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.token_kind.hash(state);
@@ -56,100 +74,618 @@ impl PartialEq for Symbol {
 }
 
 impl Symbol {
+    /// Creates a symbol.
+    ///
+    /// A synthetic symbol, which is not lexed from the input but inserted by a tool, must have an
+    /// empty range at the offset where it is inserted, see [`Symbol::is_synthetic`].
     pub fn new(token_kind: TokenKind, text: Option<&str>, range: TextRange, delta: Delta) -> Self {
         Self {
             token_kind,
             text: text.map(|s| s.to_string()).map(SmolStr::from),
             range,
             delta,
+            in_preprocessor: false,
         }
     }
 
     pub fn text(&self) -> SmolStr {
-        match &self.token_kind {
-            TokenKind::Operator(op) => return op.text(),
-            TokenKind::PreprocDir(dir) => {
-                if matches!(
-                    self.token_kind,
-                    TokenKind::PreprocDir(PreprocDir::MPragma)
-                        | TokenKind::PreprocDir(PreprocDir::MInclude)
-                        | TokenKind::PreprocDir(PreprocDir::MTryinclude)
-                ) {
-                    return self.text.clone().unwrap();
-                }
-                return dir.text();
+        match self.text_ref() {
+            Some(text) => text.into(),
+            None => self
+                .token_kind
+                .static_text()
+                .expect("tokens without a stored text have a static text")
+                .into(),
+        }
+    }
+
+    /// Returns a borrow of the text of the symbol if its kind has a variable text, such as
+    /// identifiers, literals and comments, without cloning it.
+    ///
+    /// Kinds with a fixed text, such as keywords and operators, return [`None`]. Their text is given
+    /// by [`TokenKind::static_text`]. Newlines and line continuations are the exception when
+    /// [`LexerBuilder::normalize_newlines`] is disabled, as they keep their text from the input.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let mut lexer = SourcepawnLexer::new("int foo");
+    /// assert_eq!(lexer.next().unwrap().text_ref(), None);
+    /// assert_eq!(lexer.next().unwrap().text_ref(), Some("foo"));
+    /// ```
+    pub fn text_ref(&self) -> Option<&str> {
+        match self.token_kind {
+            TokenKind::Newline | TokenKind::LineContinuation => self.text.as_deref(),
+            kind if kind.static_text().is_some() => None,
+            _ => self.text.as_deref(),
+        }
+    }
+
+    /// Returns the text of the symbol's [range](Symbol::range) in `source`, or [`None`] if the range
+    /// is out of bounds or does not fall on char boundaries.
+    ///
+    /// Unlike indexing `source` with the range, this does not panic when `source` is not the
+    /// input the symbol was lexed from, for instance after an edit of the document.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let symbol = SourcepawnLexer::new("int foo").nth(1).unwrap();
+    /// assert_eq!(symbol.slice_checked("int foo"), Some("foo"));
+    /// assert_eq!(symbol.slice_checked("int"), None);
+    /// ```
+    pub fn slice_checked<'a>(&self, source: &'a str) -> Option<&'a str> {
+        source.get(Range::<usize>::from(self.range))
+    }
+
+    /// Returns the line and column of the start of the symbol, using the [`LineIndex`] of the input
+    /// it was lexed from.
+    ///
+    /// Lines and columns are zero-based, and columns are in bytes. `\r\n` is a single line break.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{LineCol, LineIndex, SourcepawnLexer};
+    ///
+    /// let input = "int foo;\r\nint bar;";
+    /// let index = LineIndex::new(input);
+    /// let symbol = SourcepawnLexer::new(input).nth(5).unwrap();
+    /// assert_eq!(symbol.start_position(&index), LineCol { line: 1, col: 4 });
+    /// assert_eq!(symbol.end_position(&index), LineCol { line: 1, col: 7 });
+    /// ```
+    pub fn start_position(&self, line_index: &LineIndex) -> LineCol {
+        line_index.line_col(self.range.start())
+    }
+
+    /// Returns the line and column of the end of the symbol, see [`Symbol::start_position`].
+    ///
+    /// The end of a [Newline](TokenKind::Newline) is at the start of the next line.
+    pub fn end_position(&self, line_index: &LineIndex) -> LineCol {
+        line_index.line_col(self.range.end())
+    }
+
+    /// Returns the name of the identifier, or [`None`] if the symbol is not an identifier.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let symbol = SourcepawnLexer::new("foo").next().unwrap();
+    /// assert_eq!(symbol.as_identifier(), Some("foo"));
+    /// ```
+    pub fn as_identifier(&self) -> Option<&str> {
+        if self.token_kind == TokenKind::Identifier {
+            return self.text_ref();
+        }
+
+        None
+    }
+
+    /// Returns the kind of the symbol if it is a [keyword](TokenKind::keyword_text), [`None`]
+    /// otherwise.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{SourcepawnLexer, TokenKind};
+    ///
+    /// let symbols: Vec<_> = SourcepawnLexer::new("int foo").collect();
+    /// assert_eq!(symbols[0].as_keyword(), Some(TokenKind::Int));
+    /// assert_eq!(symbols[1].as_keyword(), None);
+    /// ```
+    pub fn as_keyword(&self) -> Option<TokenKind> {
+        self.token_kind.keyword_text().map(|_| self.token_kind)
+    }
+
+    /// Returns the text of the literal, including its quotes or prefix, or [`None`] if the symbol
+    /// is not a literal.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let symbol = SourcepawnLexer::new("0x1F").next().unwrap();
+    /// assert_eq!(symbol.literal_text(), Some("0x1F"));
+    /// ```
+    pub fn literal_text(&self) -> Option<&str> {
+        if let TokenKind::Literal(_) = self.token_kind {
+            return self.text_ref();
+        }
+
+        None
+    }
+
+    /// Returns the fixed spelling of the symbol's kind, such as `if` or `+=`, without allocating.
+    ///
+    /// Returns an empty string for kinds whose text varies, such as identifiers, literals and
+    /// comments, see [`TokenKind::static_text`]. Use [`Symbol::as_identifier`],
+    /// [`Symbol::literal_text`] or [`Symbol::text_ref`] for those. A newline is always `\n`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let mut lexer = SourcepawnLexer::new("if foo");
+    /// assert_eq!(lexer.next().unwrap().canonical(), "if");
+    /// assert_eq!(lexer.next().unwrap().canonical(), "");
+    /// ```
+    pub fn canonical(&self) -> &'static str {
+        self.token_kind.static_text().unwrap_or_default()
+    }
+
+    /// Returns whether the kind of the symbol is one of the given kinds.
+    ///
+    /// See [`TokenKind::matches_any`].
+    pub fn kind_is_any_of(&self, kinds: &[TokenKind]) -> bool {
+        self.token_kind.matches_any(kinds)
+    }
+
+    /// Returns whether the symbols have the same kind and text, regardless of their [range](Self::range)
+    /// and [delta](Self::delta).
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let foo = SourcepawnLexer::new("foo").next().unwrap();
+    /// let indented_foo = SourcepawnLexer::new("  foo").next().unwrap();
+    /// assert!(foo.eq_ignoring_position(&indented_foo));
+    /// ```
+    pub fn eq_ignoring_position(&self, other: &Symbol) -> bool {
+        self.token_kind == other.token_kind && self.text() == other.text()
+    }
+
+    /// Returns whether the symbol is synthetic, i.e it has an empty range and is not the
+    /// [Eof](TokenKind::Eof) token.
+    ///
+    /// The lexer never emits such symbols, but tools can insert them, for instance a missing `;`.
+    /// A synthetic symbol has no text in the input, so [`reconstruct`](crate::reconstruct) skips
+    /// it, and [`token_diff`](crate::token_diff) never matches it with a lexed symbol.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{Symbol, TextRange, TokenKind};
+    ///
+    /// let semicolon = Symbol::new(TokenKind::Semicolon, None, TextRange::empty(7.into()), 0);
+    /// assert!(semicolon.is_synthetic());
+    /// ```
+    pub fn is_synthetic(&self) -> bool {
+        self.range.is_empty() && self.token_kind != TokenKind::Eof
+    }
+
+    /// Returns whether the symbol is trivia, see [`TokenKind::is_trivia`].
+    pub fn is_trivia(&self) -> bool {
+        self.token_kind.is_trivia()
+    }
+
+    /// Returns whether the symbol is a [comment](TokenKind::Comment).
+    pub fn is_comment(&self) -> bool {
+        matches!(self.token_kind, TokenKind::Comment(_))
+    }
+
+    /// Returns whether the symbol accesses a member, see [`TokenKind::is_access_operator`].
+    pub fn is_access_operator(&self) -> bool {
+        self.token_kind.is_access_operator()
+    }
+
+    /// Returns whether the symbol is a [keyword](TokenKind::keyword_text), see
+    /// [`Symbol::as_keyword`].
+    pub fn is_keyword(&self) -> bool {
+        self.as_keyword().is_some()
+    }
+
+    /// Returns whether the symbol could start the target of an assignment, i.e an identifier,
+    /// `this` or an opening parenthesis.
+    ///
+    /// This is a heuristic for simple lints: it only looks at the symbol itself, so `foo` is
+    /// accepted in `foo(1) = 2` and `(` is accepted in `(1 + 2)`.
+    pub fn is_assignment_target_start(&self) -> bool {
+        matches!(
+            self.token_kind,
+            TokenKind::Identifier | TokenKind::This | TokenKind::LParen
+        )
+    }
+
+    /// Returns whether the symbol is a `...` which marks the variadic parameter of a function
+    /// signature, given the previous significant symbol `prev`.
+    ///
+    /// This is a heuristic: the `...` is a variadic marker if it follows `(`, `,`, a tag such as
+    /// `any` or a [type keyword](TokenSet::TYPE_KEYWORDS), or the `]` of an array type, as in
+    /// `any ...` or `const char[] ...`. Any other `...`, for instance after an operator or at the
+    /// start of the input, is not considered a variadic marker.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let symbols: Vec<_> = SourcepawnLexer::new("f(int x, any ...)").collect();
+    /// assert!(symbols[6].is_variadic_marker(Some(&symbols[5])));
+    /// ```
+    pub fn is_variadic_marker(&self, prev: Option<&Symbol>) -> bool {
+        if self.token_kind != TokenKind::Operator(Operator::Ellipses) {
+            return false;
+        }
+        let Some(prev) = prev else {
+            return false;
+        };
+
+        matches!(
+            prev.token_kind,
+            TokenKind::LParen | TokenKind::Comma | TokenKind::Identifier | TokenKind::RBracket
+        ) || TokenSet::TYPE_KEYWORDS.contains(prev.token_kind)
+    }
+
+    /// Returns the content of a string or char literal without its quotes and with its escape
+    /// sequences decoded, or [`None`] if the symbol is not a string or char literal or if one of
+    /// its escape sequences is malformed.
+    ///
+    /// Escape sequences start with `ctrl_char`, which is `\` unless changed with
+    /// `#pragma ctrlchar`. See [`SourcepawnLexer::ctrl_char`].
+    pub fn unescape(&self, ctrl_char: char) -> Option<String> {
+        let quote = match self.token_kind {
+            TokenKind::Literal(Literal::StringLiteral) => '"',
+            TokenKind::Literal(Literal::CharLiteral) => '\'',
+            _ => return None,
+        };
+        let text = self.text_ref()?;
+        let content = text.strip_prefix(quote)?.strip_suffix(quote)?;
+
+        unescape(content, ctrl_char)
+    }
+
+    /// Returns the content of a string literal without its quotes and with its escape sequences
+    /// decoded, or [`None`] if the symbol is not a string literal or if one of its escape sequences
+    /// is malformed.
+    ///
+    /// Line continuations are removed from the content. This is [`Symbol::unescape`] with the
+    /// default `\` control character.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let symbol = SourcepawnLexer::new(r#""a\tb""#).next().unwrap();
+    /// assert_eq!(symbol.string_value(), Some("a\tb".to_string()));
+    /// ```
+    pub fn string_value(&self) -> Option<String> {
+        if self.token_kind != TokenKind::Literal(Literal::StringLiteral) {
+            return None;
+        }
+
+        self.unescape(DEFAULT_CTRL_CHAR)
+    }
+
+    /// Returns the range of each malformed escape sequence of a string or char literal, such as
+    /// `\q` or a `\x` without digits, with a [`LexError::InvalidEscape`] error.
+    ///
+    /// Unterminated strings are validated too, and a `\` at their end is malformed. Other symbols
+    /// have no escape sequences. Escape sequences start with `ctrl_char`, see
+    /// [`Symbol::unescape`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{LexError, SourcepawnLexer, TextRange};
+    ///
+    /// let symbol = SourcepawnLexer::new(r#""a\qb""#).next().unwrap();
+    /// assert_eq!(
+    ///     symbol.validate_escapes('\\'),
+    ///     vec![(TextRange::new(2.into(), 4.into()), LexError::InvalidEscape)]
+    /// );
+    /// ```
+    pub fn validate_escapes(&self, ctrl_char: char) -> Vec<(TextRange, LexError)> {
+        let content = match (self.token_kind, self.text_ref()) {
+            (TokenKind::Literal(Literal::StringLiteral), Some(text)) => text
+                .strip_prefix('"')
+                .and_then(|text| text.strip_suffix('"')),
+            (TokenKind::Literal(Literal::CharLiteral), Some(text)) => text
+                .strip_prefix('\'')
+                .and_then(|text| text.strip_suffix('\'')),
+            (TokenKind::Error(LexError::UnterminatedString), Some(text)) => text.strip_prefix('"'),
+            _ => None,
+        };
+        let Some(content) = content else {
+            return vec![];
+        };
+        let start = self.range.start() + TextSize::from(1);
+
+        invalid_escapes(content, ctrl_char)
+            .into_iter()
+            .map(|range| (span_to_textrange(range) + start, LexError::InvalidEscape))
+            .collect()
+    }
+
+    /// Returns the argument of a directive which consumes its line, or [`None`] if the symbol is not
+    /// such a directive or if it has no argument.
+    ///
+    /// Those directives are `#file`, `#leaving`, `#pragma`, `#include`, `#tryinclude`, `#error` and
+    /// `#warning`. Line continuations and block comments are removed from the argument.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let symbol = SourcepawnLexer::new("#pragma semicolon 1").next().unwrap();
+    /// assert_eq!(symbol.directive_argument(), Some("semicolon 1".to_string()));
+    /// ```
+    pub fn directive_argument(&self) -> Option<String> {
+        let directive = match self.token_kind {
+            TokenKind::PreprocDir(PreprocDir::MFile) => "#file",
+            TokenKind::PreprocDir(PreprocDir::MLeaving) => "#leaving",
+            TokenKind::PreprocDir(PreprocDir::MPragma) => "#pragma",
+            TokenKind::PreprocDir(PreprocDir::MInclude) => "#include",
+            TokenKind::PreprocDir(PreprocDir::MTryinclude) => "#tryinclude",
+            TokenKind::PreprocDir(PreprocDir::MError) => "#error",
+            TokenKind::PreprocDir(PreprocDir::MWarning) => "#warning",
+            _ => return None,
+        };
+        let argument = directive_arguments(self.text_ref()?, directive)?;
+        if argument.is_empty() {
+            return None;
+        }
+
+        Some(argument)
+    }
+
+    /// Returns the name and the value of a `#pragma` directive, or [`None`] if the symbol is not a
+    /// `#pragma` directive or if it has no name.
+    ///
+    /// Line continuations and block comments are removed from the name and the value, and the
+    /// value is trimmed.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let symbol = SourcepawnLexer::new("#pragma deprecated Use Foo instead").next().unwrap();
+    /// let pragma = symbol.as_pragma().unwrap();
+    /// assert_eq!(pragma.name, "deprecated");
+    /// assert_eq!(pragma.value.as_deref(), Some("Use Foo instead"));
+    /// ```
+    pub fn as_pragma(&self) -> Option<Pragma> {
+        if self.token_kind != TokenKind::PreprocDir(PreprocDir::MPragma) {
+            return None;
+        }
+        let (name, value) = split_pragma(self.text_ref()?)?;
+
+        Some(Pragma { name, value })
+    }
+
+    /// Returns the range of the message of a `#pragma deprecated` directive, or [`None`] if the
+    /// symbol is not such a directive or if it has no message.
+    ///
+    /// The range of the directive itself covers the whole pragma, including its line
+    /// continuations. The message range starts after the `deprecated` name and ends with the
+    /// last non-whitespace character of the directive, so it includes any line continuation or
+    /// block comment inside the message. See [`Symbol::directive_argument`] for the text without
+    /// them.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{SourcepawnLexer, TextRange};
+    ///
+    /// let symbol = SourcepawnLexer::new("#pragma deprecated Use Bar\n").next().unwrap();
+    /// assert_eq!(symbol.message_range(), Some(TextRange::new(19.into(), 26.into())));
+    /// ```
+    pub fn message_range(&self) -> Option<TextRange> {
+        if self.token_kind != TokenKind::PreprocDir(PreprocDir::MPragma) {
+            return None;
+        }
+        let text = self.text_ref()?;
+        let rest = skip_blanks(text.strip_prefix("#pragma")?);
+        let rest = rest.strip_prefix("deprecated")?;
+        let message = skip_blanks(rest);
+        if message.len() == rest.len() && !message.is_empty() {
+            // The name of the pragma is longer, such as `deprecatedfoo`.
+            return None;
+        }
+        let start = text.len() - message.len();
+        let end = text.trim_end().len();
+        if start >= end {
+            return None;
+        }
+
+        Some(span_to_textrange(start..end) + self.range.start())
+    }
+
+    /// Splits a comment which ends the text of a directive which consumes its line off that
+    /// directive, and returns the directive without the comment, followed by the comment. Any
+    /// whitespace after the comment is dropped.
+    ///
+    /// Returns [`None`] if the symbol is not such a directive or if its text does not end with a
+    /// comment. Those directives are `#file`, `#leaving`, `#pragma`, `#include`, `#tryinclude`,
+    /// `#error` and `#warning`. The lexer usually emits trailing comments as separate tokens, but
+    /// a block comment which ends the input is part of the directive.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{Comment, SourcepawnLexer, TokenKind};
+    ///
+    /// let symbol = SourcepawnLexer::new("#pragma semicolon 1 /* foo */").next().unwrap();
+    /// let (pragma, comment) = symbol.extract_trailing_comment().unwrap();
+    /// assert_eq!(pragma.text(), "#pragma semicolon 1 ");
+    /// assert_eq!(comment.token_kind, TokenKind::Comment(Comment::BlockComment));
+    /// assert_eq!(comment.text(), "/* foo */");
+    /// ```
+    pub fn extract_trailing_comment(&self) -> Option<(Symbol, Symbol)> {
+        if !matches!(
+            self.token_kind,
+            TokenKind::PreprocDir(
+                PreprocDir::MFile
+                    | PreprocDir::MLeaving
+                    | PreprocDir::MPragma
+                    | PreprocDir::MInclude
+                    | PreprocDir::MTryinclude
+                    | PreprocDir::MError
+                    | PreprocDir::MWarning
+            )
+        ) {
+            return None;
+        }
+        let text = self.text_ref()?;
+        let (comment_range, comment) = trailing_comment(text)?;
+        let start = self.range.start();
+        let directive_range = TextRange::at(start, TextSize::from(comment_range.start as u32));
+        let directive_text = &text[..comment_range.start];
+        let comment_text = &text[comment_range.clone()];
+        let comment_range = span_to_textrange(comment_range) + start;
+
+        Some((
+            Symbol::new(
+                self.token_kind,
+                Some(directive_text),
+                directive_range,
+                self.delta,
+            ),
+            Symbol::new(
+                TokenKind::Comment(comment),
+                Some(comment_text),
+                comment_range,
+                0,
+            ),
+        ))
+    }
+
+    /// Returns whether the symbol is a documentation comment, i.e a line comment starting with
+    /// `///` or a block comment starting with `/**`.
+    ///
+    /// Comments made of more slashes or stars, such as `////` or `/***`, are usually separators
+    /// and are not documentation comments. Neither is the empty block comment `/**/`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let symbol = SourcepawnLexer::new("/// Does foo.").next().unwrap();
+    /// assert!(symbol.is_doc_comment());
+    /// let symbol = SourcepawnLexer::new("// foo").next().unwrap();
+    /// assert!(!symbol.is_doc_comment());
+    /// ```
+    pub fn is_doc_comment(&self) -> bool {
+        let Some(text) = self.text_ref() else {
+            return false;
+        };
+        match self.token_kind {
+            TokenKind::Comment(Comment::LineComment) => {
+                text.starts_with("///") && !text.starts_with("////")
             }
-            TokenKind::Comment(_) | TokenKind::Literal(_) | TokenKind::Identifier => {
-                return self.text.clone().unwrap()
+            TokenKind::Comment(Comment::BlockComment) => {
+                text.starts_with("/**") && !text.starts_with("/***") && text != "/**/"
             }
-            TokenKind::Newline => "\n",
-            TokenKind::LineContinuation => "\\\n",
-            TokenKind::Bool => "bool",
-            TokenKind::Break => "break",
-            TokenKind::Case => "case",
-            TokenKind::Char => "char",
-            TokenKind::Class => "class",
-            TokenKind::Const => "const",
-            TokenKind::Continue => "continue",
-            TokenKind::Decl => "decl",
-            TokenKind::Default => "default",
-            TokenKind::Defined => "defined",
-            TokenKind::Delete => "delete",
-            TokenKind::Do => "do",
-            TokenKind::Else => "else",
-            TokenKind::Enum => "enum",
-            TokenKind::False => "false",
-            TokenKind::Float => "float",
-            TokenKind::OldFloat => "Float",
-            TokenKind::OldString => "String",
-            TokenKind::For => "for",
-            TokenKind::Forward => "forward",
-            TokenKind::Functag => "functag",
-            TokenKind::Function => "function",
-            TokenKind::If => "if",
-            TokenKind::Int => "int",
-            TokenKind::InvalidFunction => "INVALID_FUNCTION",
-            TokenKind::Methodmap => "methodmap",
-            TokenKind::Native => "native",
-            TokenKind::Null => "null",
-            TokenKind::New => "new",
-            TokenKind::Object => "object",
-            TokenKind::Property => "property",
-            TokenKind::Public => "public",
-            TokenKind::Return => "return",
-            TokenKind::Sizeof => "sizeof",
-            TokenKind::Static => "static",
-            TokenKind::Stock => "stock",
-            TokenKind::Struct => "struct",
-            TokenKind::Switch => "switch",
-            TokenKind::This => "this",
-            TokenKind::True => "true",
-            TokenKind::Typedef => "typedef",
-            TokenKind::Typeset => "typeset",
-            TokenKind::Union => "union",
-            TokenKind::Using => "using",
-            TokenKind::ViewAs => "view_as",
-            TokenKind::Void => "void",
-            TokenKind::While => "while",
-            TokenKind::Nullable => "__nullable__",
-            TokenKind::Intrinsics => "__intrinsics__",
-            TokenKind::Semicolon => ";",
-            TokenKind::LBrace => "{",
-            TokenKind::RBrace => "}",
-            TokenKind::LParen => "(",
-            TokenKind::RParen => ")",
-            TokenKind::LBracket => "[",
-            TokenKind::RBracket => "]",
-            TokenKind::Comma => ",",
-            TokenKind::Qmark => "?",
-            TokenKind::Colon => ":",
-            TokenKind::Scope => "::",
-            TokenKind::Dot => ".",
-            TokenKind::Unknown => "",
-            TokenKind::Underscore => "_",
-            TokenKind::Eof => "\0",
-        }
-        .into()
+            _ => false,
+        }
+    }
+
+    /// Returns the path of an `#include` or `#tryinclude` directive, without its `<>` or `""`
+    /// delimiters, or [`None`] if the symbol is not an include directive or if it has no path.
+    ///
+    /// The path is returned verbatim: SourcePawn does not process escapes in include paths, so a
+    /// `\` is a path separator, like `/`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let symbol = SourcepawnLexer::new(r#"#include "a\b.inc""#).next().unwrap();
+    /// assert_eq!(symbol.include_path(), Some(r"a\b.inc".to_string()));
+    /// ```
+    pub fn include_path(&self) -> Option<String> {
+        self.as_include().map(|include| include.path.to_string())
+    }
+
+    /// Returns the path of an `#include` or `#tryinclude` directive and its delimiters, or [`None`]
+    /// if the symbol is not an include directive or if it has no path.
+    ///
+    /// Line continuations and block comments are removed, and the path is returned verbatim like
+    /// in [`Symbol::include_path`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{IncludeKind, SourcepawnLexer};
+    ///
+    /// let symbol = SourcepawnLexer::new("#include <sourcemod>").next().unwrap();
+    /// let include = symbol.as_include().unwrap();
+    /// assert_eq!(include.path, "sourcemod");
+    /// assert_eq!(include.kind, IncludeKind::Chevron);
+    /// ```
+    pub fn as_include(&self) -> Option<Include> {
+        if !matches!(
+            self.token_kind,
+            TokenKind::PreprocDir(PreprocDir::MInclude | PreprocDir::MTryinclude)
+        ) {
+            return None;
+        }
+        let argument = self.directive_argument()?;
+        let (path, kind) = if let Some(chevron) = argument.strip_prefix('<') {
+            (
+                chevron.split('>').next().unwrap_or_default(),
+                IncludeKind::Chevron,
+            )
+        } else if let Some(quoted) = argument.strip_prefix('"') {
+            (
+                quoted.split('"').next().unwrap_or_default(),
+                IncludeKind::Quote,
+            )
+        } else {
+            (argument.as_str(), IncludeKind::Bare)
+        };
+        if path.is_empty() {
+            return None;
+        }
+
+        Some(Include {
+            path: path.into(),
+            kind,
+        })
+    }
+
+    /// Returns the path of a `#file` directive, or [`None`] if the symbol is not a `#file` directive
+    /// or if it has no path.
+    ///
+    /// `#file` is emitted by the compiler in preprocessed output to set the name of the current
+    /// file. The path can be quoted or bare.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let symbol = SourcepawnLexer::new("#file \"plugin.sp\"").next().unwrap();
+    /// assert_eq!(symbol.file_directive_path(), Some("plugin.sp".to_string()));
+    /// ```
+    pub fn file_directive_path(&self) -> Option<String> {
+        if self.token_kind != TokenKind::PreprocDir(PreprocDir::MFile) {
+            return None;
+        }
+        let argument = self.directive_argument()?;
+        let path = match argument.strip_prefix('"') {
+            Some(quoted) => quoted.split('"').next().unwrap_or_default(),
+            None => argument.as_str(),
+        };
+        if path.is_empty() {
+            return None;
+        }
+
+        Some(path.to_string())
     }
 
     pub fn to_int(&self) -> Option<u32> {
@@ -160,6 +696,46 @@ impl Symbol {
         None
     }
 
+    /// Returns the value of an integer literal as an [`i64`], or [`None`] if the symbol is not an
+    /// integer literal or if its value does not fit.
+    ///
+    /// Unlike [`Symbol::to_int`], values above [`u32::MAX`] are supported. Float and char literals
+    /// are not converted.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let symbol = SourcepawnLexer::new("4000000000").next().unwrap();
+    /// assert_eq!(symbol.to_i64(), Some(4_000_000_000));
+    /// ```
+    pub fn to_i64(&self) -> Option<i64> {
+        if let TokenKind::Literal(lit) = &self.token_kind {
+            return lit.to_i64(&self.text());
+        }
+
+        None
+    }
+
+    /// Returns the value of a float literal, or [`None`] if the symbol is not a number literal.
+    ///
+    /// Integer literals are promoted to a float. Digit separators (`_`) are ignored.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let symbol = SourcepawnLexer::new("1_000.5e-2").next().unwrap();
+    /// assert_eq!(symbol.to_float(), Some(10.005));
+    /// ```
+    pub fn to_float(&self) -> Option<f64> {
+        if let TokenKind::Literal(lit) = &self.token_kind {
+            return lit.to_float(&self.text());
+        }
+
+        None
+    }
+
     pub fn inline_text(&self) -> SmolStr {
         let text = self.text();
         match &self.token_kind {
@@ -167,14 +743,23 @@ impl Symbol {
                 return text.replace("\\\n", "").replace("\\\r\n", "").into()
             }
             TokenKind::Comment(com) => {
-                if *com == Comment::BlockComment {
+                if matches!(
+                    com,
+                    Comment::BlockComment | Comment::UnterminatedBlockComment
+                ) {
                     return text.replace('\n', "").replace("\r\n", "").into();
                 }
             }
             TokenKind::PreprocDir(dir) => {
                 if matches!(
                     *dir,
-                    PreprocDir::MPragma | PreprocDir::MInclude | PreprocDir::MTryinclude
+                    PreprocDir::MFile
+                        | PreprocDir::MLeaving
+                        | PreprocDir::MPragma
+                        | PreprocDir::MInclude
+                        | PreprocDir::MTryinclude
+                        | PreprocDir::MError
+                        | PreprocDir::MWarning
                 ) {
                     return text.replace("\\\n", "").replace("\\\r\n", "").into();
                 }
@@ -186,6 +771,51 @@ impl Symbol {
     }
 }
 
+/// State of a [`SourcepawnLexer`] at an offset of its input, from which lexing can be resumed, see
+/// [`SourcepawnLexer::from_checkpoint`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Checkpoint {
+    /// Offset at which lexing resumes, usually the end of a symbol.
+    pub offset: TextSize,
+
+    /// Whether or not the offset is in a preprocessing statement, see
+    /// [`SourcepawnLexer::in_preprocessor`].
+    pub in_preprocessor: bool,
+
+    /// Whether or not the offset is in a `#define`, where `%0` to `%9` are
+    /// [macro parameters](TokenKind::MacroParam).
+    pub in_define: bool,
+
+    /// Control character at the offset, see [`SourcepawnLexer::ctrl_char`].
+    pub ctrl_char: char,
+
+    /// Rational tag at the offset, see [`SourcepawnLexer::rational_tag`].
+    pub rational_tag: Option<SmolStr>,
+}
+
+impl Default for Checkpoint {
+    fn default() -> Self {
+        Self {
+            offset: TextSize::from(0),
+            in_preprocessor: false,
+            in_define: false,
+            ctrl_char: DEFAULT_CTRL_CHAR,
+            rational_tag: None,
+        }
+    }
+}
+
+/// The last symbol emitted by a [`SourcepawnLexer`], without its text, which is a slice of the
+/// input when it is stored.
+#[derive(Debug, Clone, Copy)]
+struct LastSymbol {
+    token_kind: TokenKind,
+    range: TextRange,
+    delta: Delta,
+    has_text: bool,
+    in_preprocessor: bool,
+}
+
 /// Sourcepawn lexer.
 ///
 /// # Example
@@ -202,10 +832,23 @@ pub struct SourcepawnLexer<'a> {
     lexer: Lexer<'a, Token>,
     in_preprocessor: bool,
     prev_range: Option<TextRange>,
+    /// Offset of the start of the line of the last token, only tracked with
+    /// [`LexerBuilder::line_relative_deltas`].
+    line_start: u32,
     eof: bool,
+    ctrl_char: char,
+    rational_tag: Option<SmolStr>,
+    in_define: bool,
+    last_symbol: Option<LastSymbol>,
+    /// Kinds and ranges of the last significant symbols, oldest first, at most
+    /// [`LexerBuilder::look_behind`] of them.
+    recent: VecDeque<(TokenKind, TextRange)>,
+    defines: DefineTracker,
+    diagnostics: Vec<(TextRange, LexError)>,
+    config: LexerBuilder,
 }
 
-impl SourcepawnLexer<'_> {
+impl<'a> SourcepawnLexer<'a> {
     /// Creates a new Sourcepawn lexer.
     ///
     /// # Example
@@ -215,12 +858,164 @@ impl SourcepawnLexer<'_> {
     /// let lexer = SourcepawnLexer::new("int foo = 0;");
     /// ```
     pub fn new(input: &str) -> SourcepawnLexer {
+        LexerBuilder::default().build(input)
+    }
+
+    /// Creates a lexer which resumes lexing the input at a [checkpoint](Checkpoint), taken with
+    /// [`SourcepawnLexer::checkpoint`] by a previous lexer over the same input.
+    ///
+    /// This allows re-lexing only the part of the input after an edit. The ranges of the symbols
+    /// are still offsets in the whole input, and the [delta](Delta) of the first symbol is its
+    /// distance from the offset of the checkpoint, so resuming at a checkpoint yields the same
+    /// symbols as a full lex. The rest of the state, i.e the tracked macros and the
+    /// [diagnostics](SourcepawnLexer::diagnostics), starts afresh.
+    ///
+    /// The lexer has the default configuration, see [`LexerBuilder::build_from_checkpoint`] to
+    /// configure it.
+    ///
+    /// # Panics
+    /// Panics if the offset of the checkpoint is past the end of the input or is not on a char
+    /// boundary.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{Checkpoint, SourcepawnLexer, TokenKind};
+    ///
+    /// let checkpoint = Checkpoint { offset: 8.into(), ..Default::default() };
+    /// let mut lexer = SourcepawnLexer::from_checkpoint("int foo;  int bar;", checkpoint);
+    /// let symbol = lexer.next().unwrap();
+    /// assert_eq!(symbol.token_kind, TokenKind::Int);
+    /// assert_eq!(symbol.delta, 2);
+    /// ```
+    pub fn from_checkpoint(input: &str, checkpoint: Checkpoint) -> SourcepawnLexer<'_> {
+        LexerBuilder::default().build_from_checkpoint(input, checkpoint)
+    }
+
+    pub(crate) fn with_checkpoint(
+        input: &str,
+        config: LexerBuilder,
+        checkpoint: Checkpoint,
+    ) -> SourcepawnLexer<'_> {
+        let offset = checkpoint.offset;
+        assert!(
+            input.is_char_boundary(offset.into()),
+            "offset {offset:?} is not a char boundary of the input"
+        );
+        let mut lexer = SourcepawnLexer::with_config(input, config);
+        lexer.lexer.bump(offset.into());
+        lexer.in_preprocessor = checkpoint.in_preprocessor;
+        lexer.in_define = checkpoint.in_define;
+        lexer.ctrl_char = checkpoint.ctrl_char;
+        lexer.rational_tag = checkpoint.rational_tag;
+        if offset > TextSize::from(0) {
+            lexer.prev_range = Some(TextRange::empty(offset));
+        }
+        if lexer.config.line_relative_deltas {
+            lexer.line_start = input[..usize::from(offset)]
+                .rfind('\n')
+                .map_or(0, |idx| idx as u32 + 1);
+        }
+
+        lexer
+    }
+
+    /// Returns the state needed to resume lexing after the last symbol returned by
+    /// [`next`](Iterator::next), see [`SourcepawnLexer::from_checkpoint`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let input = "#define FOO 1\nint foo;";
+    /// let mut lexer = SourcepawnLexer::new(input);
+    /// lexer.nth(1);
+    /// let checkpoint = lexer.checkpoint();
+    /// assert!(checkpoint.in_preprocessor);
+    /// assert_eq!(
+    ///     SourcepawnLexer::from_checkpoint(input, checkpoint).collect::<Vec<_>>(),
+    ///     lexer.collect::<Vec<_>>()
+    /// );
+    /// ```
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            offset: self
+                .prev_range
+                .map_or_else(|| TextSize::from(0), |range| range.end()),
+            in_preprocessor: self.in_preprocessor,
+            in_define: self.in_define,
+            ctrl_char: self.ctrl_char,
+            rational_tag: self.rational_tag.clone(),
+        }
+    }
+
+    pub(crate) fn with_config(input: &str, config: LexerBuilder) -> SourcepawnLexer<'_> {
+        let extras = Extras {
+            max_token_len: config.max_token_len,
+            token_too_long: false,
+            unterminated_comment: false,
+        };
         SourcepawnLexer {
-            lexer: Token::lexer(input),
+            lexer: Token::lexer_with_extras(input, extras),
             in_preprocessor: false,
             prev_range: None,
+            line_start: 0,
             eof: false,
+            ctrl_char: DEFAULT_CTRL_CHAR,
+            rational_tag: None,
+            in_define: false,
+            last_symbol: None,
+            recent: VecDeque::with_capacity(config.look_behind),
+            defines: DefineTracker::default(),
+            diagnostics: Vec::new(),
+            config,
+        }
+    }
+
+    /// Returns whether the input contains at least one token which is not [trivia](TokenKind::is_trivia).
+    ///
+    /// Lexing stops at the first such token, so this is cheap even for large inputs.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// assert!(!SourcepawnLexer::has_significant_tokens("// comment\n"));
+    /// assert!(SourcepawnLexer::has_significant_tokens("int foo;"));
+    /// ```
+    pub fn has_significant_tokens(input: &str) -> bool {
+        SourcepawnLexer::new(input)
+            .any(|symbol| !symbol.token_kind.is_trivia() && symbol.token_kind != TokenKind::Eof)
+    }
+
+    /// Lexes the input and returns the range of the first error, without collecting the tokens.
+    ///
+    /// An error is an [Error](TokenKind::Error) token, an
+    /// [unterminated block comment](Comment::UnterminatedBlockComment), reported as
+    /// [`LexError::UnterminatedComment`], or an [Unknown](TokenKind::Unknown) token, reported as
+    /// [`LexError::UnexpectedCharacter`]. Lexing stops at the first error.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{LexError, SourcepawnLexer};
+    ///
+    /// assert!(SourcepawnLexer::validate("int foo;").is_ok());
+    /// let (_, error) = SourcepawnLexer::validate("int foo = \"bar;").unwrap_err();
+    /// assert_eq!(error, LexError::UnterminatedString);
+    /// ```
+    pub fn validate(input: &str) -> Result<(), (TextRange, LexError)> {
+        for symbol in SourcepawnLexer::new(input) {
+            let error = match symbol.token_kind {
+                TokenKind::Error(error) => error,
+                TokenKind::Comment(Comment::UnterminatedBlockComment) => {
+                    LexError::UnterminatedComment
+                }
+                TokenKind::Unknown => LexError::UnexpectedCharacter,
+                _ => continue,
+            };
+            return Err((symbol.range, error));
         }
+
+        Ok(())
     }
 
     /// Returns whether or not we are in a preprocessing statement.
@@ -234,13 +1029,223 @@ impl SourcepawnLexer<'_> {
     /// In this example, everything before the line `int foo;` is considered preprocessing.
     /// See the [tests](https://github.com/Sarrus1/sourcepawn-lexer/blob/main/tests/define.rs) for more examples.
     pub fn in_preprocessor(&self) -> bool {
-        self.in_preprocessor && !self.eof
+        self.in_preprocessor
+    }
+
+    /// Returns the control character, which starts escape sequences in string and char literals.
+    ///
+    /// It defaults to `\` and is changed by `#pragma ctrlchar`, e.g. after `#pragma ctrlchar '%'`
+    /// the string `"a%nb"` contains a line break. Pass it to [`Symbol::unescape`] to decode
+    /// literals accordingly.
+    ///
+    /// Note that the lexer itself always uses `\` to find the end of string and char literals.
+    pub fn ctrl_char(&self) -> char {
+        self.ctrl_char
+    }
+
+    /// Returns the tag of rational numbers set by the last `#pragma rational`, if any.
+    ///
+    /// # Example
+    /// ```cpp
+    /// #pragma rational Float
+    /// ```
+    ///
+    /// After this line, the rational tag is `Float`.
+    pub fn rational_tag(&self) -> Option<&str> {
+        self.rational_tag.as_deref()
+    }
+
+    /// Returns the macros defined so far with `#define`, and their value, if
+    /// [`LexerBuilder::track_defines`] is enabled.
+    ///
+    /// The value of a macro is the text of its tokens, separated by a space and without comments.
+    /// Macros removed with `#undef` are not returned.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::LexerBuilder;
+    ///
+    /// let mut lexer = LexerBuilder::new().track_defines(true).build("#define FOO 1 + 2");
+    /// lexer.by_ref().count();
+    /// assert_eq!(lexer.defines().get("FOO").unwrap(), "1 + 2");
+    /// ```
+    pub fn defines(&self) -> &HashMap<SmolStr, SmolStr> {
+        self.defines.defines()
+    }
+
+    /// Returns the diagnostics found so far, with the range they apply to.
+    ///
+    /// Unlike [`TokenKind::Error`] tokens, diagnostics do not replace any token. They are reported
+    /// for [`LexError::RedefinedMacro`], see [`LexerBuilder::track_defines`], and
+    /// [`LexError::IncompleteLineContinuation`].
+    pub fn diagnostics(&self) -> &[(TextRange, LexError)] {
+        &self.diagnostics
+    }
+
+    /// Returns the text of the symbol which was last returned by [`next`](Iterator::next),
+    /// borrowed from the input, or an empty string before the first call.
+    ///
+    /// Unlike [`Symbol::text`], this never allocates, so it is cheaper when the text of most
+    /// symbols is needed. The text is the exact slice of the input covered by the
+    /// [range](Symbol::range) of the symbol, so a `\r\n` newline is not normalized, and the text
+    /// of the [Eof](TokenKind::Eof) token is empty.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let mut lexer = SourcepawnLexer::new("int foo;");
+    /// lexer.nth(1);
+    /// assert_eq!(lexer.current_text(), "foo");
+    /// ```
+    pub fn current_text(&self) -> &'a str {
+        match self.last_symbol {
+            Some(symbol) => &self.lexer.source()[symbol.range],
+            None => "",
+        }
+    }
+
+    /// Returns the symbol which was last returned by [`next`](Iterator::next), including the
+    /// [Eof](TokenKind::Eof) token, or [`None`] before the first call.
+    ///
+    /// The lexer does not keep a copy of each symbol, the symbol is rebuilt from the input.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::SourcepawnLexer;
+    ///
+    /// let mut lexer = SourcepawnLexer::new("int foo;");
+    /// assert!(lexer.last_symbol().is_none());
+    /// let symbol = lexer.next();
+    /// assert_eq!(lexer.last_symbol(), symbol);
+    /// ```
+    pub fn last_symbol(&self) -> Option<Symbol> {
+        let last = self.last_symbol?;
+        let text = last.has_text.then(|| &self.lexer.source()[last.range]);
+        let mut symbol = Symbol::new(last.token_kind, text, last.range, last.delta);
+        symbol.in_preprocessor = last.in_preprocessor;
+
+        Some(symbol)
+    }
+
+    /// Returns the kinds and the ranges of the last `n` significant symbols returned by
+    /// [`next`](Iterator::next), i.e which are not [trivia](TokenKind::is_trivia), oldest first.
+    ///
+    /// The lexer only remembers as many symbols as set with [`LexerBuilder::look_behind`], 2 by
+    /// default, so fewer symbols are returned if `n` is larger, or if fewer symbols were lexed.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{Operator, SourcepawnLexer, TokenKind};
+    ///
+    /// let mut lexer = SourcepawnLexer::new("view_as<int>(foo)");
+    /// lexer.nth(1);
+    /// let kinds: Vec<_> = lexer.recent_significant(2).map(|(kind, _)| kind).collect();
+    /// assert_eq!(kinds, vec![TokenKind::ViewAs, TokenKind::Operator(Operator::Lt)]);
+    /// ```
+    pub fn recent_significant(
+        &self,
+        n: usize,
+    ) -> impl ExactSizeIterator<Item = (TokenKind, TextRange)> + DoubleEndedIterator + '_ {
+        self.recent
+            .range(self.recent.len().saturating_sub(n)..)
+            .copied()
+    }
+
+    /// Updates the state which depends on the emitted symbols, and returns the symbol.
+    fn emit(&mut self, mut symbol: Symbol) -> Option<Symbol> {
+        symbol.in_preprocessor = self.in_preprocessor;
+        if self.config.track_defines {
+            if let Some(diagnostic) = self.defines.update(&symbol, self.in_preprocessor) {
+                self.diagnostics.push(diagnostic);
+            }
+        }
+        if !symbol.is_trivia() && self.config.look_behind > 0 {
+            if self.recent.len() == self.config.look_behind {
+                self.recent.pop_front();
+            }
+            self.recent.push_back((symbol.token_kind, symbol.range));
+        }
+        self.last_symbol = Some(LastSymbol {
+            token_kind: symbol.token_kind,
+            range: symbol.range,
+            delta: symbol.delta,
+            has_text: symbol.text.is_some(),
+            in_preprocessor: symbol.in_preprocessor,
+        });
+
+        Some(symbol)
+    }
+
+    fn update_pragma_state(&mut self, pragma: &str) {
+        let Some((name, value)) = split_pragma(pragma) else {
+            return;
+        };
+        match name.as_str() {
+            "ctrlchar" => self.update_ctrl_char(value.as_deref()),
+            "rational" => {
+                // The tag can be followed by the precision, e.g. `#pragma rational Float(3)`.
+                self.rational_tag = value.and_then(|value| {
+                    let end = value
+                        .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+                        .unwrap_or(value.len());
+                    if end == 0 {
+                        return None;
+                    }
+                    Some(SmolStr::from(&value[..end]))
+                });
+            }
+            _ => (),
+        }
+    }
+
+    fn update_ctrl_char(&mut self, value: Option<&str>) {
+        let Some(value) = value else {
+            // `#pragma ctrlchar` without argument restores the default.
+            self.ctrl_char = DEFAULT_CTRL_CHAR;
+            return;
+        };
+        let ctrl_char = match value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+            Some(quoted) => {
+                let mut chars = quoted.chars();
+                chars.next().filter(|_| chars.next().is_none())
+            }
+            None => value.parse().ok().and_then(char::from_u32),
+        };
+        if let Some(ctrl_char) = ctrl_char {
+            self.ctrl_char = ctrl_char;
+        }
+    }
+
+    /// Consumes the `#!` line at the start of the input, without its line break.
+    fn lex_shebang(&mut self) -> Symbol {
+        let line = self.lexer.remainder().lines().next().unwrap_or_default();
+        self.lexer.bump(line.len());
+        let range = span_to_textrange(self.lexer.span());
+        Symbol {
+            token_kind: TokenKind::Shebang,
+            text: Some(SmolStr::from(self.lexer.slice())),
+            range,
+            delta: self.delta(range),
+            in_preprocessor: false,
+        }
     }
 
     fn delta(&mut self, range: TextRange) -> Delta {
-        let delta = if let Some(prev_range) = &self.prev_range {
-            let start: u32 = range.start().into();
-            let end: u32 = prev_range.end().into();
+        let start: u32 = range.start().into();
+        let prev_end: Option<u32> = self.prev_range.map(|prev_range| prev_range.end().into());
+        let delta = if self.config.line_relative_deltas {
+            // Tokens are only separated by spaces and tabs, so the line starts after the last
+            // line break of the previous tokens.
+            let line_start = self.line_start;
+            if let Some(idx) = self.lexer.source()[range].rfind('\n') {
+                self.line_start = start + idx as u32 + 1;
+            }
+            match prev_end {
+                Some(end) if end > line_start => start as i32 - end as i32,
+                _ => (start - line_start) as i32,
+            }
+        } else if let Some(end) = prev_end {
             start as i32 - end as i32
         } else {
             Delta::default()
@@ -251,93 +1256,260 @@ impl SourcepawnLexer<'_> {
     }
 }
 
+/// Returns whether a token spanning several lines ends the preprocessor directive it is part of,
+/// i.e whether its text contains a line break but no line continuation.
+///
+/// A `\` followed by `\r\n` is a line continuation too, so a CRLF continuation keeps the
+/// directive going like a LF one.
+fn ends_directive(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    let mut line_break = false;
+    for (idx, &byte) in bytes.iter().enumerate() {
+        if byte != b'\n' {
+            continue;
+        }
+        let before = &bytes[..idx];
+        let before = before.strip_suffix(b"\r").unwrap_or(before);
+        if before.ends_with(b"\\") {
+            return false;
+        }
+        line_break = true;
+    }
+
+    line_break
+}
+
+/// Returns the range and the kind of the comment which ends the text, if any, ignoring trailing
+/// whitespace.
+///
+/// Comments inside string and char literals are ignored.
+fn trailing_comment(text: &str) -> Option<(Range<usize>, Comment)> {
+    let mut trailing = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((idx, ch)) = chars.next() {
+        let next_ch = chars.peek().map(|(_, ch)| *ch);
+        match (ch, next_ch) {
+            ('/', Some('/')) => return Some((idx..text.len(), Comment::LineComment)),
+            ('/', Some('*')) => {
+                chars.next();
+                let Some(end) = text[idx + 2..].find("*/") else {
+                    return Some((idx..text.len(), Comment::UnterminatedBlockComment));
+                };
+                let end = idx + 2 + end + 2;
+                while chars.next_if(|(idx, _)| *idx < end).is_some() {}
+                trailing = Some((idx..end, Comment::BlockComment));
+            }
+            ('"' | '\'', _) => {
+                trailing = None;
+                while let Some((_, literal_ch)) = chars.next() {
+                    if literal_ch == '\\' {
+                        chars.next();
+                    } else if literal_ch == ch {
+                        break;
+                    }
+                }
+            }
+            (ch, _) if ch.is_whitespace() => (),
+            _ => trailing = None,
+        }
+    }
+
+    trailing
+}
+
+/// Skips the whitespace and the line continuations at the start of the text.
+fn skip_blanks(mut text: &str) -> &str {
+    loop {
+        text = text.trim_start_matches([' ', '\t', '\x0b', '\x0c']);
+        match text
+            .strip_prefix("\\\n")
+            .or_else(|| text.strip_prefix("\\\r\n"))
+        {
+            Some(rest) => text = rest,
+            None => return text,
+        }
+    }
+}
+
 fn span_to_textrange(span: Range<usize>) -> TextRange {
     TextRange::new((span.start as u32).into(), (span.end as u32).into())
 }
 
+/// Returns the length of the rest of an attribute after its first `[`, if it is closed on the
+/// same line.
+fn attribute_len(remainder: &str) -> Option<usize> {
+    let body = remainder.strip_prefix('[')?;
+    let line = &body[..body.find('\n').unwrap_or(body.len())];
+
+    line.find("]]").map(|end| end + 3)
+}
+
 impl Iterator for SourcepawnLexer<'_> {
     type Item = Symbol;
 
     fn next(&mut self) -> Option<Symbol> {
-        lazy_static! {
-            static ref RE1: Regex = Regex::new(r"\n").unwrap();
+        if self.eof {
+            // The Eof token has already been emitted, never lex past it.
+            return None;
         }
-        lazy_static! {
-            static ref RE2: Regex = Regex::new(r"\\\r?\n").unwrap();
+        if self.prev_range.is_none()
+            && self.config.allow_shebang
+            && self.lexer.remainder().starts_with("#!")
+        {
+            let symbol = self.lex_shebang();
+            return self.emit(symbol);
         }
-        let token = self.lexer.next();
-        if token.is_none() && !self.eof {
-            // Reached EOF
+        let token = loop {
+            match self.lexer.next() {
+                Some(Token::Whitespace) if !self.config.emit_whitespace => continue,
+                token => break token,
+            }
+        };
+        let Some(token) = token else {
+            // Reached EOF, which always ends a preprocessor directive.
             self.eof = true;
+            self.in_preprocessor = false;
             let range = span_to_textrange(self.lexer.span());
-            return Some(Symbol {
+            let symbol = Symbol {
                 token_kind: TokenKind::Eof,
                 text: None,
                 range,
                 delta: self.delta(range),
-            });
+                in_preprocessor: false,
+            };
+            return self.emit(symbol);
+        };
+        if token == Token::UnterminatedStringLiteral && self.lexer.remainder() == "\\" {
+            // A line continuation needs a line break, so the string stays unterminated. Keep the
+            // backslash in the string instead of lexing it as an unknown token.
+            self.lexer.bump(1);
+            let end = span_to_textrange(self.lexer.span()).end();
+            self.diagnostics.push((
+                TextRange::at(end - TextSize::from(1), TextSize::from(1)),
+                LexError::IncompleteLineContinuation,
+            ));
         }
-        let token = token?;
 
-        let text = match token {
+        let mut text = match token {
             Token::Identifier
             | Token::IntegerLiteral
             | Token::HexLiteral
             | Token::BinaryLiteral
             | Token::OctodecimalLiteral
             | Token::StringLiteral
+            | Token::UnterminatedStringLiteral
             | Token::CharLiteral
             | Token::FloatLiteral
+            | Token::HexFloatLiteral
             | Token::BlockComment
             | Token::LineComment
+            | Token::Whitespace
+            | Token::MFile
+            | Token::MLeaving
             | Token::MPragma
             | Token::MInclude
-            | Token::MTryinclude => Some(SmolStr::from(self.lexer.slice())),
+            | Token::MTryinclude
+            | Token::MError
+            | Token::MWarning => Some(SmolStr::from(self.lexer.slice())),
+            Token::Newline | Token::LineContinuation if !self.config.normalize_newlines => {
+                Some(SmolStr::from(self.lexer.slice()))
+            }
             _ => None,
         };
 
         match token {
             Token::StringLiteral
             | Token::BlockComment
+            | Token::MFile
+            | Token::MLeaving
             | Token::MPragma
             | Token::MInclude
-            | Token::MTryinclude => {
-                if matches!(token, Token::MPragma | Token::MInclude | Token::MTryinclude) {
+            | Token::MTryinclude
+            | Token::MError
+            | Token::MWarning => {
+                if matches!(
+                    token,
+                    Token::MFile
+                        | Token::MLeaving
+                        | Token::MPragma
+                        | Token::MInclude
+                        | Token::MTryinclude
+                        | Token::MError
+                        | Token::MWarning
+                ) {
                     self.in_preprocessor = true;
                 }
-                // Safe unwrap here as those tokens have text.
-                let text = text.clone().unwrap();
-                let line_breaks: Vec<_> = RE1.find_iter(text.as_str()).collect();
-                let line_continuations: Vec<_> = RE2.find_iter(text.as_str()).collect();
-
-                if line_continuations.last().is_none() && line_breaks.last().is_some() {
+                if token == Token::MPragma {
+                    self.update_pragma_state(self.lexer.slice());
+                }
+                if ends_directive(self.lexer.slice()) {
                     self.in_preprocessor = false;
                 }
             }
-            Token::MDefine
+            Token::MAssert
+            | Token::MDefine
             | Token::MDeprecate
             | Token::MIf
             | Token::MElse
             | Token::MElseif
             | Token::MEndinput
-            | Token::MFile
             | Token::MOptionalNewdecls
             | Token::MOptionalSemi
             | Token::MRequireNewdecls
             | Token::MRequireSemi
             | Token::MUndef
-            | Token::MEndif
-            | Token::MLeaving => self.in_preprocessor = true,
+            | Token::MEndif => self.in_preprocessor = true,
             Token::Newline => self.in_preprocessor = false,
             _ => {}
         }
-        let token_kind = TokenKind::try_from(token).ok()?;
+        if token == Token::MDefine {
+            self.in_define = true;
+        } else if !self.in_preprocessor {
+            self.in_define = false;
+        }
+        let mut token_kind = TokenKind::try_from(token).ok()?;
+        if std::mem::take(&mut self.lexer.extras.token_too_long) {
+            token_kind = TokenKind::Error(LexError::TokenTooLong);
+        }
+        if std::mem::take(&mut self.lexer.extras.unterminated_comment) {
+            token_kind = TokenKind::Comment(Comment::UnterminatedBlockComment);
+        }
+        if token_kind == TokenKind::Literal(Literal::CharLiteral) && self.lexer.slice() == "''" {
+            token_kind = TokenKind::Error(LexError::EmptyChar);
+        }
+        if token_kind == TokenKind::Operator(Operator::Percent)
+            && self.in_define
+            && self
+                .lexer
+                .remainder()
+                .starts_with(|c: char| c.is_ascii_digit())
+        {
+            // Macro parameters are a single digit, `%10` is `%1` followed by `0`.
+            self.lexer.bump(1);
+            token_kind = TokenKind::MacroParam;
+            text = Some(SmolStr::from(self.lexer.slice()));
+        }
+        if token_kind == TokenKind::LBracket && self.config.attribute_syntax {
+            if let Some(len) = attribute_len(self.lexer.remainder()) {
+                self.lexer.bump(len);
+                token_kind = TokenKind::Attribute;
+                text = Some(SmolStr::from(self.lexer.slice()));
+            }
+        }
+        if token_kind.keyword_text().is_some() && !self.config.dialect.has_keyword(token_kind) {
+            token_kind = TokenKind::Identifier;
+            text = Some(SmolStr::from(self.lexer.slice()));
+        }
         let range = span_to_textrange(self.lexer.span());
-        Some(Symbol {
+        let symbol = Symbol {
             token_kind,
             text,
             range,
             delta: self.delta(range),
-        })
+            in_preprocessor: false,
+        };
+        self.emit(symbol)
     }
 }
+
+impl FusedIterator for SourcepawnLexer<'_> {}
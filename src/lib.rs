@@ -1,7 +1,24 @@
+mod adapters;
+mod builder;
+mod bytes;
+mod define;
+mod escape;
+mod include;
 mod lexer;
+mod line_index;
+mod modifier;
+mod peekable;
 mod pragma;
 mod token;
 mod token_kind;
+mod token_set;
+mod utils;
 
-pub use self::{lexer::Delta, lexer::SourcepawnLexer, lexer::Symbol, token_kind::*};
-pub use text_size::{TextRange, TextSize, TextLen};
\ No newline at end of file
+pub use self::{
+    adapters::Piece, builder::Dialect, builder::LexerBuilder, bytes::ByteLexer, include::Include,
+    include::IncludeKind, lexer::Checkpoint, lexer::Delta, lexer::SourcepawnLexer, lexer::Symbol,
+    line_index::LineCol, line_index::LineIndex, modifier::Modifier, modifier::ModifierSet,
+    peekable::PeekableSourcepawnLexer, pragma::Pragma, token_kind::*, token_set::TokenSet,
+    utils::*,
+};
+pub use text_size::{TextLen, TextRange, TextSize};
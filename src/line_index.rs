@@ -0,0 +1,112 @@
+use text_size::TextSize;
+
+/// Zero-based line and column of a position in the input.
+///
+/// The column is a byte offset from the start of the line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineCol {
+    /// Zero-based line.
+    pub line: u32,
+
+    /// Zero-based column, in bytes.
+    pub col: u32,
+}
+
+/// Converts byte offsets of an input into [lines and columns](LineCol).
+///
+/// A line ends after each `\n`, so `\r\n` is a single line break. A line break which follows a `\`
+/// is a [line continuation](crate::TokenKind::LineContinuation), and whether it starts a new line
+/// is set by [`LineIndex::continuation_resets_line`].
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{LineCol, LineIndex};
+///
+/// let index = LineIndex::new("int foo;\nint bar;");
+/// assert_eq!(index.line_col(13.into()), LineCol { line: 1, col: 4 });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    /// Start of every physical line.
+    line_starts: Vec<TextSize>,
+
+    /// Start of the lines which do not follow a line continuation.
+    logical_line_starts: Vec<TextSize>,
+
+    continuation_resets_line: bool,
+}
+
+impl LineIndex {
+    /// Creates the index of an input. Line continuations start a new line.
+    pub fn new(input: &str) -> Self {
+        let mut line_starts = vec![TextSize::from(0)];
+        let mut logical_line_starts = vec![TextSize::from(0)];
+        let bytes = input.as_bytes();
+        for (i, _) in input.match_indices('\n') {
+            let start = TextSize::from(i as u32 + 1);
+            line_starts.push(start);
+            let before = bytes[..i].strip_suffix(b"\r").unwrap_or(&bytes[..i]);
+            if !before.ends_with(b"\\") {
+                logical_line_starts.push(start);
+            }
+        }
+
+        Self {
+            line_starts,
+            logical_line_starts,
+            continuation_resets_line: true,
+        }
+    }
+
+    /// Sets whether a [line continuation](crate::TokenKind::LineContinuation) starts a new line.
+    /// Defaults to `true`.
+    ///
+    /// When `true`, lines and columns are physical, as displayed by an editor: the token which
+    /// follows a line continuation is at the start of a new line.
+    ///
+    /// When `false`, lines and columns are logical, as seen by the preprocessor: a line
+    /// continuation is part of its line, and the columns of the tokens which follow it keep
+    /// counting from the start of that line. This is the line of a continued `#define`, for
+    /// instance.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{LineCol, LineIndex};
+    ///
+    /// let input = "#define FOO \\\n1";
+    /// let index = LineIndex::new(input);
+    /// assert_eq!(index.line_col(14.into()), LineCol { line: 1, col: 0 });
+    /// let index = index.continuation_resets_line(false);
+    /// assert_eq!(index.line_col(14.into()), LineCol { line: 0, col: 14 });
+    /// ```
+    pub fn continuation_resets_line(mut self, continuation_resets_line: bool) -> Self {
+        self.continuation_resets_line = continuation_resets_line;
+        self
+    }
+
+    /// Returns the line and column of a byte offset.
+    ///
+    /// An offset past the end of the input is on the last line.
+    pub fn line_col(&self, offset: TextSize) -> LineCol {
+        let line_starts = if self.continuation_resets_line {
+            &self.line_starts
+        } else {
+            &self.logical_line_starts
+        };
+        let line = line_starts.partition_point(|&start| start <= offset) - 1;
+
+        LineCol {
+            line: line as u32,
+            col: u32::from(offset - line_starts[line]),
+        }
+    }
+
+    /// Returns the number of lines of the input.
+    pub fn line_count(&self) -> usize {
+        if self.continuation_resets_line {
+            self.line_starts.len()
+        } else {
+            self.logical_line_starts.len()
+        }
+    }
+}
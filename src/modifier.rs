@@ -0,0 +1,98 @@
+use crate::TokenKind;
+
+/// A lexical modifier of a declaration, given by a keyword which precedes its name, see
+/// [`infer_modifiers`](crate::infer_modifiers).
+///
+/// Those map to the semantic token modifiers of the Language Server Protocol, for instance
+/// [`Const`](Modifier::Const) to `readonly` and [`Static`](Modifier::Static) to `static`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Modifier {
+    /// `const`
+    Const,
+
+    /// `static`
+    Static,
+
+    /// `native`
+    Native,
+
+    /// `forward`
+    Forward,
+
+    /// `public`
+    Public,
+
+    /// `stock`
+    Stock,
+}
+
+impl Modifier {
+    /// Returns the modifier given by the keyword, or [`None`] if the kind is not a modifier
+    /// keyword.
+    pub const fn from_kind(kind: TokenKind) -> Option<Self> {
+        let modifier = match kind {
+            TokenKind::Const => Self::Const,
+            TokenKind::Static => Self::Static,
+            TokenKind::Native => Self::Native,
+            TokenKind::Forward => Self::Forward,
+            TokenKind::Public => Self::Public,
+            TokenKind::Stock => Self::Stock,
+            _ => return None,
+        };
+
+        Some(modifier)
+    }
+}
+
+/// A set of [`Modifier`], stored as a bitset.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{Modifier, ModifierSet};
+///
+/// let set = ModifierSet::EMPTY.with(Modifier::Const);
+/// assert!(set.contains(Modifier::Const));
+/// assert!(!set.contains(Modifier::Static));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ModifierSet {
+    bits: u8,
+}
+
+impl ModifierSet {
+    /// The empty set.
+    pub const EMPTY: ModifierSet = ModifierSet { bits: 0 };
+
+    /// Creates a set which contains the given modifiers.
+    pub const fn new(modifiers: &[Modifier]) -> Self {
+        let mut set = Self::EMPTY;
+        let mut i = 0;
+        while i < modifiers.len() {
+            set = set.with(modifiers[i]);
+            i += 1;
+        }
+
+        set
+    }
+
+    /// Returns a copy of the set which also contains the modifier.
+    pub const fn with(mut self, modifier: Modifier) -> Self {
+        self.bits |= 1 << modifier as u8;
+        self
+    }
+
+    /// Adds the modifier to the set.
+    pub fn insert(&mut self, modifier: Modifier) {
+        *self = self.with(modifier);
+    }
+
+    /// Returns whether the set contains the modifier.
+    pub const fn contains(&self, modifier: Modifier) -> bool {
+        self.bits & (1 << modifier as u8) != 0
+    }
+
+    /// Returns whether the set is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+}
@@ -0,0 +1,87 @@
+use std::{collections::VecDeque, iter::FusedIterator};
+
+use crate::{SourcepawnLexer, Symbol};
+
+/// A [`SourcepawnLexer`] with lookahead, which buffers the symbols it peeks instead of cloning the
+/// lexer.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{PeekableSourcepawnLexer, SourcepawnLexer, TokenKind};
+///
+/// let mut lexer = PeekableSourcepawnLexer::new(SourcepawnLexer::new("int foo;"));
+/// assert_eq!(lexer.peek_nth(1).unwrap().token_kind, TokenKind::Identifier);
+/// assert_eq!(lexer.next().unwrap().token_kind, TokenKind::Int);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PeekableSourcepawnLexer<'a> {
+    lexer: SourcepawnLexer<'a>,
+    /// Symbols lexed ahead.
+    peeked: VecDeque<Symbol>,
+    in_preprocessor: bool,
+}
+
+impl<'a> PeekableSourcepawnLexer<'a> {
+    /// Wraps a lexer, which may have already been advanced.
+    pub fn new(lexer: SourcepawnLexer<'a>) -> Self {
+        let in_preprocessor = lexer.in_preprocessor();
+        Self {
+            lexer,
+            peeked: VecDeque::new(),
+            in_preprocessor,
+        }
+    }
+
+    /// Returns the symbol which the next call to `next` will return, without consuming it.
+    pub fn peek(&mut self) -> Option<&Symbol> {
+        self.peek_nth(0)
+    }
+
+    /// Returns the `n`th symbol after the last consumed symbol, without consuming it. `peek_nth(0)`
+    /// is the same as [`peek`](PeekableSourcepawnLexer::peek).
+    pub fn peek_nth(&mut self, n: usize) -> Option<&Symbol> {
+        while self.peeked.len() <= n {
+            let symbol = self.lexer.next()?;
+            self.peeked.push_back(symbol);
+        }
+        self.peeked.get(n)
+    }
+
+    /// Returns whether or not we are in a preprocessing statement, see
+    /// [`SourcepawnLexer::in_preprocessor`].
+    ///
+    /// This reflects the last consumed symbol: peeking past the end of a directive does not change it.
+    pub fn in_preprocessor(&self) -> bool {
+        self.in_preprocessor
+    }
+
+    /// Returns the wrapped lexer.
+    ///
+    /// Its state, such as [`SourcepawnLexer::in_preprocessor`], reflects the last peeked symbol
+    /// rather than the last consumed one.
+    pub fn inner(&self) -> &SourcepawnLexer<'a> {
+        &self.lexer
+    }
+}
+
+impl<'a> From<SourcepawnLexer<'a>> for PeekableSourcepawnLexer<'a> {
+    fn from(lexer: SourcepawnLexer<'a>) -> Self {
+        Self::new(lexer)
+    }
+}
+
+impl Iterator for PeekableSourcepawnLexer<'_> {
+    type Item = Symbol;
+
+    fn next(&mut self) -> Option<Symbol> {
+        let symbol = match self.peeked.pop_front() {
+            Some(symbol) => symbol,
+            None => self.lexer.next()?,
+        };
+        self.in_preprocessor = symbol.in_preprocessor;
+
+        Some(symbol)
+    }
+}
+
+impl FusedIterator for PeekableSourcepawnLexer<'_> {}
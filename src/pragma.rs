@@ -1,7 +1,64 @@
 use logos::Lexer;
+use smol_str::SmolStr;
 
 use crate::token::Token;
 
+/// A `#pragma` directive split into its name and its value, see
+/// [`Symbol::as_pragma`](crate::Symbol::as_pragma).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pragma {
+    /// Name of the pragma, such as `semicolon` or `deprecated`.
+    pub name: SmolStr,
+
+    /// Value of the pragma, i.e the rest of the directive after the name, if any.
+    pub value: Option<SmolStr>,
+}
+
+/// Returns the arguments of a directive which consumes its line, such as `#pragma`, without
+/// the directive itself, line continuations and block comments.
+pub(crate) fn directive_arguments(text: &str, directive: &str) -> Option<String> {
+    let text = text
+        .strip_prefix(directive)?
+        .replace("\\\r\n", "")
+        .replace("\\\n", "");
+    let mut stripped = String::with_capacity(text.len());
+    let mut rest = text.as_str();
+    while let Some(start) = rest.find("/*") {
+        stripped.push_str(&rest[..start]);
+        rest = match rest[start..].find("*/") {
+            Some(end) => &rest[start + end + 2..],
+            None => "",
+        };
+    }
+    stripped.push_str(rest);
+
+    Some(stripped.trim().to_string())
+}
+
+/// Splits the text of a `#pragma` directive into the name of the pragma and its argument, if any.
+///
+/// Line continuations and block comments are removed from the text.
+///
+/// # Example
+/// `#pragma deprecated Use Foo instead` is split into `deprecated` and `Use Foo instead`.
+pub(crate) fn split_pragma(text: &str) -> Option<(SmolStr, Option<SmolStr>)> {
+    let text = directive_arguments(text, "#pragma")?;
+    let (name, value) = match text.find(char::is_whitespace) {
+        Some(idx) => (&text[..idx], text[idx..].trim()),
+        None => (text.as_str(), ""),
+    };
+    if name.is_empty() {
+        return None;
+    }
+    let value = if value.is_empty() {
+        None
+    } else {
+        Some(value.into())
+    };
+
+    Some((name.into(), value))
+}
+
 pub fn lex_pragma_arguments(lex: &mut Lexer<Token>) -> Option<()> {
     let mut in_block_comment = false;
     let mut looking_for_newline = false;
@@ -9,6 +66,11 @@ pub fn lex_pragma_arguments(lex: &mut Lexer<Token>) -> Option<()> {
     let mut offset = 0;
     let mut iter = lex.remainder().chars().peekable();
     while let Some(ch) = iter.next() {
+        if lex.slice().len() + offset > lex.extras.max_token_len {
+            // Do not scan any further than the limit.
+            lex.extras.token_too_long = true;
+            return Some(());
+        }
         let mut next_ch = '\0';
         if let Some(ch) = iter.peek() {
             next_ch = *ch;
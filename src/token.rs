@@ -1,11 +1,26 @@
 use crate::pragma::lex_pragma_arguments;
-use logos::Logos;
+use logos::{Lexer, Logos};
+
+/// State shared with the callbacks of the logos lexer.
+#[derive(Debug, Clone)]
+pub struct Extras {
+    /// Maximum length in bytes a callback is allowed to scan for a single token.
+    pub max_token_len: usize,
+
+    /// Whether the last token was cut because it exceeded [`max_token_len`](Self::max_token_len).
+    pub token_too_long: bool,
+
+    /// Whether the last token was a block comment which is not closed before the end of the input.
+    pub unterminated_comment: bool,
+}
 
 #[derive(Logos, Debug, Clone, PartialEq, Eq)]
+#[logos(extras = Extras)]
 // white space
 #[logos(subpattern ws = r"[ \t\v\f]")]
-// escape sequence
-#[logos(subpattern es = r#"[\\](['"%?\\abefnrtv]|[0-7]+|[xu][a-fA-F0-9]+|[\r]?[\n])"#)]
+// escape sequence, malformed ones included so that they do not split the literal, see
+// `Symbol::validate_escapes`
+#[logos(subpattern es = r#"[\\]([^\r\n]|[\r]?[\n])"#)]
 pub enum Token {
     #[regex(r"[a-zA-Z_][a-zA-Z0-9_]*")]
     Identifier,
@@ -16,16 +31,23 @@ pub enum Token {
     #[regex(r"0x[0-9a-fA-F_]+")]
     HexLiteral,
 
+    #[regex(r"0x(?:[0-9a-fA-F_]+(?:\.[0-9a-fA-F_]*)?|\.[0-9a-fA-F_]+)[pP][+\-]?[0-9_]+")]
+    HexFloatLiteral,
+
     #[regex(r"0b[01_]+")]
     BinaryLiteral,
 
     #[regex(r"0o[0-7_]+")]
     OctodecimalLiteral,
 
-    #[regex(r#""([^"\\\n]|(?&es))*""#)]
+    #[regex(r#""([^"\\\r\n]|(?&es))*""#)]
     StringLiteral,
 
-    #[regex(r"'([^'\\\n]|(?&es))*'")]
+    // A string literal which is not closed before the end of the line.
+    #[regex(r#""([^"\\\r\n]|(?&es))*"#)]
+    UnterminatedStringLiteral,
+
+    #[regex(r"'([^'\\\r\n]|(?&es))*'")]
     CharLiteral,
 
     #[regex(r"(?:(?:[0-9_]+\.[0-9_]+)|(?:[0-9_]*\.[0-9_]+))(e\-?\d+)?")]
@@ -40,13 +62,12 @@ pub enum Token {
     #[regex("//[^\r\n]*")]
     LineComment,
 
-    #[token("/*",
-        |lex| {
-        lex.bump(lex.remainder().find("*/")? + 2);
-        Some(())
-    })]
+    #[token("/*", lex_block_comment)]
     BlockComment,
 
+    #[token("any")]
+    Any,
+
     #[token("bool")]
     Bool,
 
@@ -107,6 +128,9 @@ pub enum Token {
     #[token("forward")]
     Forward,
 
+    #[token("funcenum")]
+    Funcenum,
+
     #[token("functag")]
     Functag,
 
@@ -137,6 +161,9 @@ pub enum Token {
     #[token("object")]
     Object,
 
+    #[token("operator")]
+    OperatorKw,
+
     #[token("property")]
     Property,
 
@@ -191,6 +218,9 @@ pub enum Token {
     #[token("__nullable__")]
     Nullable,
 
+    #[token("#assert")]
+    MAssert,
+
     #[token("#define")]
     MDefine,
 
@@ -209,7 +239,10 @@ pub enum Token {
     #[token("#endinput")]
     MEndinput,
 
-    #[token("#file")]
+    #[token("#error", lex_pragma_arguments)]
+    MError,
+
+    #[token("#file", lex_pragma_arguments)]
     MFile,
 
     #[token("#if")]
@@ -218,7 +251,7 @@ pub enum Token {
     #[token("#include", lex_pragma_arguments)]
     MInclude,
 
-    #[token("#leaving")]
+    #[token("#leaving", lex_pragma_arguments)]
     MLeaving,
 
     #[token("__LINE__")]
@@ -245,6 +278,9 @@ pub enum Token {
     #[token("#undef")]
     MUndef,
 
+    #[token("#warning", lex_pragma_arguments)]
+    MWarning,
+
     #[token("__intrinsics__")]
     Intrinsics,
 
@@ -339,7 +375,7 @@ pub enum Token {
     AssignShr,
 
     #[token(">>>=")]
-    AssignUshl,
+    AssignUshr,
 
     #[token("<<=")]
     AssignShl,
@@ -398,7 +434,38 @@ pub enum Token {
     #[token("_")]
     Underscore,
 
+    #[regex(r"(?&ws)+")]
+    Whitespace,
+
     #[error]
-    #[regex(r"(?&ws)+", logos::skip)]
     Unknown,
 }
+
+fn lex_block_comment(lex: &mut Lexer<Token>) -> Option<()> {
+    let remainder = lex.remainder();
+    // The limit covers the `/*` which is already matched.
+    let mut window_len = lex
+        .extras
+        .max_token_len
+        .saturating_sub(lex.slice().len())
+        .min(remainder.len());
+    while !remainder.is_char_boundary(window_len) {
+        window_len -= 1;
+    }
+    match remainder[..window_len].find("*/") {
+        Some(end) => lex.bump(end + 2),
+        None => {
+            if window_len == remainder.len() {
+                // Unterminated block comment, which runs to the end of the input.
+                lex.bump(window_len);
+                lex.extras.unterminated_comment = true;
+                return Some(());
+            }
+            // Do not scan any further than the limit.
+            lex.bump(window_len);
+            lex.extras.token_too_long = true;
+        }
+    }
+
+    Some(())
+}
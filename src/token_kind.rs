@@ -1,9 +1,15 @@
-use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use smol_str::SmolStr;
+use std::fmt;
 
-use crate::token::Token;
+use crate::{
+    escape::{unescape, DEFAULT_CTRL_CHAR},
+    token::Token,
+};
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Literal {
     /// Integer literal.
     ///
@@ -67,56 +73,57 @@ pub enum Literal {
     /// 100_00.000_1e-10
     /// ```
     FloatLiteral,
+
+    /// Hexadecimal float literal, whose value is the hexadecimal mantissa multiplied by 2 to the
+    /// power of the decimal exponent. The exponent is required.
+    ///
+    /// # Examples
+    /// ```
+    /// 0x1.8p3
+    /// 0x1p-2
+    /// ```
+    HexFloatLiteral,
 }
 
 impl Literal {
+    /// Returns the digits of an integer literal, without its prefix and digit separators, and
+    /// their radix.
+    fn integer_digits(&self, text: &str) -> Option<(String, u32)> {
+        let (digits, radix) = match self {
+            Self::IntegerLiteral => (text, 10),
+            Self::BinaryLiteral => (
+                text.strip_prefix("0b")
+                    .or_else(|| text.strip_prefix("0B"))?,
+                2,
+            ),
+            Self::OctodecimalLiteral => (
+                text.strip_prefix("0o")
+                    .or_else(|| text.strip_prefix("0O"))?,
+                8,
+            ),
+            Self::HexLiteral => (
+                text.strip_prefix("0x")
+                    .or_else(|| text.strip_prefix("0X"))?,
+                16,
+            ),
+            Self::FloatLiteral
+            | Self::HexFloatLiteral
+            | Self::CharLiteral
+            | Self::StringLiteral => return None,
+        };
+
+        Some((digits.replace('_', ""), radix))
+    }
+
     pub(crate) fn to_int(&self, text: &str) -> Option<u32> {
         let mut buf = String::new();
-        let mut x_reached = false;
         match self {
-            Self::IntegerLiteral => {
-                for ch in text.chars() {
-                    if ch.is_numeric() {
-                        buf.push(ch)
-                    }
-                }
-                buf.parse().ok()
-            }
-            Self::BinaryLiteral => {
-                for ch in text.chars() {
-                    if !x_reached {
-                        if ch == 'x' {
-                            x_reached = true;
-                        }
-                    } else if ch.is_numeric() {
-                        buf.push(ch);
-                    }
-                }
-                u32::from_str_radix(&buf, 2).ok()
-            }
-            Self::OctodecimalLiteral => {
-                for ch in text.chars() {
-                    if !x_reached {
-                        if ch == 'x' {
-                            x_reached = true;
-                        }
-                    } else if ch.is_numeric() {
-                        buf.push(ch);
-                    }
-                }
-                u32::from_str_radix(&buf, 8).ok()
-            }
-            Self::HexLiteral => {
-                for ch in text.chars() {
-                    if !x_reached {
-                        if ch == 'x' {
-                            x_reached = true;
-                        }
-                    } else if ch.is_numeric() {
-                        buf.push(ch);
-                    }
-                }
-                u32::from_str_radix(&buf, 16).ok()
+            Self::IntegerLiteral
+            | Self::BinaryLiteral
+            | Self::OctodecimalLiteral
+            | Self::HexLiteral => {
+                let (digits, radix) = self.integer_digits(text)?;
+                u32::from_str_radix(&digits, radix).ok()
             }
             Self::FloatLiteral => {
                 for ch in text.chars() {
@@ -127,22 +134,90 @@ impl Literal {
                 let tmp: f32 = buf.parse().ok()?;
                 Some(tmp.trunc() as u32)
             }
+            Self::HexFloatLiteral => self.to_float(text).map(|value| value.trunc() as u32),
             Self::CharLiteral => {
-                let mut out = 0;
-                for ch in text.chars() {
-                    out += ch as u32
+                let content = text.strip_prefix('\'')?.strip_suffix('\'')?;
+                let content = unescape(content, DEFAULT_CTRL_CHAR)?;
+                let mut chars = content.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) => Some(ch as u32),
+                    _ => None,
                 }
-                Some(out)
             }
             Self::StringLiteral => None,
         }
     }
+
+    pub(crate) fn to_i64(self, text: &str) -> Option<i64> {
+        let (digits, radix) = self.integer_digits(text)?;
+        i64::from_str_radix(&digits, radix).ok()
+    }
+
+    pub(crate) fn to_float(self, text: &str) -> Option<f64> {
+        match self {
+            Self::FloatLiteral => text.replace('_', "").parse().ok(),
+            Self::HexFloatLiteral => hex_float(text),
+            Self::IntegerLiteral
+            | Self::HexLiteral
+            | Self::BinaryLiteral
+            | Self::OctodecimalLiteral => self.to_int(text).map(f64::from),
+            Self::CharLiteral | Self::StringLiteral => None,
+        }
+    }
+}
+
+/// Returns the value of a hexadecimal float literal such as `0x1.8p3`.
+fn hex_float(text: &str) -> Option<f64> {
+    let text = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))?;
+    let (mantissa, exponent) = text.split_once(['p', 'P'])?;
+    let exponent: i32 = exponent.replace('_', "").parse().ok()?;
+    let (int_digits, frac_digits) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    let mut value = 0f64;
+    let mut frac_len = 0;
+    for (ch, is_frac) in int_digits
+        .chars()
+        .map(|ch| (ch, false))
+        .chain(frac_digits.chars().map(|ch| (ch, true)))
+    {
+        if ch == '_' {
+            continue;
+        }
+        value = value * 16.0 + f64::from(ch.to_digit(16)?);
+        if is_frac {
+            frac_len += 1;
+        }
+    }
+
+    Some(value * 2f64.powi(exponent - 4 * frac_len))
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::IntegerLiteral => "integer literal",
+            Self::HexLiteral => "hexadecimal literal",
+            Self::BinaryLiteral => "binary literal",
+            Self::OctodecimalLiteral => "octal literal",
+            Self::StringLiteral => "string literal",
+            Self::CharLiteral => "char literal",
+            Self::FloatLiteral => "float literal",
+            Self::HexFloatLiteral => "hexadecimal float literal",
+        };
+        f.write_str(label)
+    }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Comment {
     /// Line comments.
     ///
+    /// A line comment always ends at the line break, even if it ends with a `\`: unlike in C, it
+    /// does not continue on the next line.
+    ///
     /// # Examples
     ///
     /// ```
@@ -157,9 +232,42 @@ pub enum Comment {
     /// /* comment */
     /// ```
     BlockComment,
+
+    /// Block comments which are not closed before the end of the input. They run to the end of
+    /// the input.
+    ///
+    /// # Examples
+    /// ```
+    /// /* comment
+    /// ```
+    UnterminatedBlockComment,
+}
+
+impl fmt::Display for Comment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::LineComment => "line comment",
+            Self::BlockComment => "block comment",
+            Self::UnterminatedBlockComment => "unterminated block comment",
+        };
+        f.write_str(label)
+    }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+/// Operators.
+///
+/// Runs of operator characters are lexed greedily: the longest operator which starts the run is
+/// taken, then lexing resumes after it. For example:
+/// - `+++` is `++` followed by `+`.
+/// - `----` is `--` followed by `--`.
+/// - `<<<` is `<<` followed by `<`.
+/// - `>>>>` is `>>>` followed by `>`, and never `>>` followed by `>>`.
+/// - `>>>=` is a single `>>>=`, and `>>>>=` is `>>>` followed by `>=`.
+///
+/// Whitespace always separates operators, so `>> >` is `>>` followed by `>`. A parser which
+/// expects several closing `>` must split the shift operators itself.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Operator {
     /// `...`
     Ellipses,
@@ -231,7 +339,8 @@ pub enum Operator {
     AssignShr,
 
     /// `>>>=`
-    AssignUshl,
+    #[cfg_attr(feature = "serde", serde(alias = "AssignUshl"))]
+    AssignUshr,
 
     /// `<<=`
     AssignShl,
@@ -274,7 +383,19 @@ pub enum Operator {
 }
 
 impl Operator {
+    /// Former name of [`Operator::AssignUshr`], kept for compatibility.
+    #[deprecated(note = "`>>>=` is a right shift, use `Operator::AssignUshr` instead")]
+    #[allow(non_upper_case_globals)]
+    pub const AssignUshl: Operator = Operator::AssignUshr;
+
     pub fn text(&self) -> SmolStr {
+        self.static_text().into()
+    }
+
+    /// Returns the spelling of the operator.
+    ///
+    /// This can be evaluated in a `const` context.
+    pub const fn static_text(self) -> &'static str {
         match self {
             self::Operator::Ellipses => "...",
             self::Operator::Plus => "+",
@@ -299,7 +420,7 @@ impl Operator {
             self::Operator::AssignBitOr => "|=",
             self::Operator::AssignBitXor => "^=",
             self::Operator::AssignShr => ">>=",
-            self::Operator::AssignUshl => ">>>=",
+            self::Operator::AssignUshr => ">>>=",
             self::Operator::AssignShl => "<<=",
             self::Operator::Increment => "++",
             self::Operator::Decrement => "--",
@@ -314,12 +435,66 @@ impl Operator {
             self::Operator::Not => "!",
             self::Operator::Tilde => "~",
         }
-        .into()
+    }
+
+    /// Returns the binary operator applied by a compound assignment, e.g `+` for `+=`, or [`None`]
+    /// if the operator is not a compound assignment.
+    pub const fn base_operator(self) -> Option<Operator> {
+        let op = match self {
+            Self::AssignAdd => Self::Plus,
+            Self::AssignSub => Self::Minus,
+            Self::AssignMul => Self::Star,
+            Self::AssignDiv => Self::Slash,
+            Self::AssignMod => Self::Percent,
+            Self::AssignBitAnd => Self::Ampersand,
+            Self::AssignBitOr => Self::Bitor,
+            Self::AssignBitXor => Self::Bitxor,
+            Self::AssignShr => Self::Shr,
+            Self::AssignUshr => Self::Ushr,
+            Self::AssignShl => Self::Shl,
+            _ => return None,
+        };
+
+        Some(op)
+    }
+
+    /// Expands a compound assignment into its binary operator and [`Assign`](Self::Assign), or
+    /// returns [`None`] if the operator is not a compound assignment.
+    ///
+    /// `x op= y` has the semantics of `x = x op y`, except that `x` is only evaluated once. This is
+    /// a building block for rewriting compound assignments, not a rewriter: the caller is
+    /// responsible for duplicating the target and for adding parentheses around `y` when needed.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::Operator;
+    ///
+    /// assert_eq!(
+    ///     Operator::AssignShr.expand_compound_assignment(),
+    ///     Some((Operator::Shr, Operator::Assign))
+    /// );
+    /// assert_eq!(Operator::Assign.expand_compound_assignment(), None);
+    /// ```
+    pub const fn expand_compound_assignment(self) -> Option<(Operator, Operator)> {
+        match self.base_operator() {
+            Some(op) => Some((op, Self::Assign)),
+            None => None,
+        }
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.static_text())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PreprocDir {
+    /// `#assert`
+    MAssert,
+
     /// `#define`
     MDefine,
 
@@ -338,7 +513,17 @@ pub enum PreprocDir {
     /// `#endinput`
     MEndinput,
 
+    /// `#error`
+    ///
+    /// Stops the compilation with the message which follows the directive. The directive consumes
+    /// its line, like `#pragma`, and its message is given by
+    /// [`Symbol::directive_argument`](crate::Symbol::directive_argument).
+    MError,
+
     /// `#file`
+    ///
+    /// Emitted by the compiler in preprocessed output, as `#file` followed by the path of the
+    /// current file. See [`Symbol::file_directive_path`](crate::Symbol::file_directive_path).
     MFile,
 
     /// `#if`
@@ -348,6 +533,11 @@ pub enum PreprocDir {
     MInclude,
 
     /// `#leaving`
+    ///
+    /// Emitted by the compiler in preprocessed output at the end of an included file, as
+    /// `#leaving` optionally followed by the path of that file. Like `#file`, the directive
+    /// consumes its line and its argument is given by
+    /// [`Symbol::directive_argument`](crate::Symbol::directive_argument).
     MLeaving,
 
     /// `__LINE__`
@@ -373,17 +563,34 @@ pub enum PreprocDir {
 
     /// `#undef`
     MUndef,
+
+    /// `#warning`
+    ///
+    /// Reports a warning with the message which follows the directive. Like
+    /// [`MError`](PreprocDir::MError), the directive consumes its line.
+    MWarning,
 }
 
 impl PreprocDir {
     pub fn text(&self) -> SmolStr {
-        match self {
+        self.static_text()
+            .expect("#pragma have their own text.")
+            .into()
+    }
+
+    /// Returns the spelling of the directive, or [`None`] for `#pragma`, whose text is the whole directive.
+    ///
+    /// This can be evaluated in a `const` context.
+    pub const fn static_text(self) -> Option<&'static str> {
+        let text = match self {
+            self::PreprocDir::MAssert => "#assert",
             self::PreprocDir::MDefine => "#define",
             self::PreprocDir::MDeprecate => "#deprecate",
             self::PreprocDir::MElse => "#else",
             self::PreprocDir::MElseif => "#elseif",
             self::PreprocDir::MEndif => "#endif",
             self::PreprocDir::MEndinput => "#endinput",
+            self::PreprocDir::MError => "#error",
             self::PreprocDir::MFile => "#file",
             self::PreprocDir::MIf => "#if",
             self::PreprocDir::MInclude => "#include",
@@ -391,17 +598,97 @@ impl PreprocDir {
             self::PreprocDir::MLine => "__LINE__",
             self::PreprocDir::MOptionalNewdecls => "#optional_newdecls",
             self::PreprocDir::MOptionalSemi => "#optional_semicolons",
-            self::PreprocDir::MPragma => unimplemented!("#pragma have their own text."),
+            self::PreprocDir::MPragma => return None,
             self::PreprocDir::MRequireNewdecls => "#require_newdecls",
             self::PreprocDir::MRequireSemi => "#require_semicolons",
             self::PreprocDir::MTryinclude => "#try_include",
             self::PreprocDir::MUndef => "#undef",
-        }
-        .into()
+            self::PreprocDir::MWarning => "#warning",
+        };
+
+        Some(text)
+    }
+}
+
+impl fmt::Display for PreprocDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.static_text().unwrap_or("#pragma"))
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LexError {
+    /// A block comment or a preprocessor directive is longer than the limit set with
+    /// [`LexerBuilder::max_token_len`](crate::LexerBuilder::max_token_len).
+    ///
+    /// The token is cut at the limit and lexing resumes right after it.
+    TokenTooLong,
+
+    /// A string literal is not closed before the end of the line.
+    ///
+    /// The token stops before the line break and lexing resumes on the next line.
+    UnterminatedString,
+
+    /// A sequence of bytes which is not valid UTF-8, see
+    /// [`SourcepawnLexer::from_bytes`](crate::SourcepawnLexer::from_bytes).
+    ///
+    /// The text of the token is the replacement character `U+FFFD`.
+    InvalidUtf8,
+
+    /// The input starts with a UTF-16 byte order mark, see
+    /// [`SourcepawnLexer::from_bytes`](crate::SourcepawnLexer::from_bytes).
+    UnsupportedEncoding,
+
+    /// A macro is defined again with a different value. Only reported as a diagnostic, see
+    /// [`LexerBuilder::track_defines`](crate::LexerBuilder::track_defines).
+    RedefinedMacro,
+
+    /// A string literal ends with a `\` at the end of the input, which would be a line
+    /// continuation if a line break followed it. Only reported as a diagnostic, the string is an
+    /// [`UnterminatedString`](LexError::UnterminatedString) which includes the `\`.
+    IncompleteLineContinuation,
+
+    /// A malformed escape sequence in a string or char literal, such as `\q`. Only reported by
+    /// [`Symbol::validate_escapes`](crate::Symbol::validate_escapes).
+    InvalidEscape,
+
+    /// A block comment is not closed before the end of the input. Only reported by
+    /// [`SourcepawnLexer::validate`](crate::SourcepawnLexer::validate), the lexer emits a
+    /// [`Comment::UnterminatedBlockComment`] token.
+    UnterminatedComment,
+
+    /// A character which does not start any token. Only reported by
+    /// [`SourcepawnLexer::validate`](crate::SourcepawnLexer::validate), the lexer emits a
+    /// [`TokenKind::Unknown`] token.
+    UnexpectedCharacter,
+
+    /// A char literal without a character, `''`. The text of the token is the two quotes.
+    EmptyChar,
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::TokenTooLong => "token too long",
+            Self::UnterminatedString => "unterminated string",
+            Self::InvalidUtf8 => "invalid UTF-8",
+            Self::UnsupportedEncoding => "unsupported encoding",
+            Self::RedefinedMacro => "redefined macro",
+            Self::IncompleteLineContinuation => "incomplete line continuation",
+            Self::InvalidEscape => "invalid escape sequence",
+            Self::UnterminatedComment => "unterminated comment",
+            Self::UnexpectedCharacter => "unexpected character",
+            Self::EmptyChar => "empty char literal",
+        };
+        f.write_str(label)
+    }
+}
+
+/// Kind of a [`Symbol`](crate::Symbol).
+///
+/// With the `serde` feature, kinds are serialized as their [stable name](TokenKind::kind_name).
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[repr(u16)]
 pub enum TokenKind {
     Identifier,
@@ -409,8 +696,12 @@ pub enum TokenKind {
     Comment(Comment),
     Operator(Operator),
     PreprocDir(PreprocDir),
+
+    /// Malformed input. The text of the token is the text that caused the error.
+    Error(LexError),
     Newline,
     LineContinuation,
+    Any,
     Bool,
     Break,
     Case,
@@ -431,6 +722,7 @@ pub enum TokenKind {
     OldString,
     For,
     Forward,
+    Funcenum,
     Functag,
     Function,
     If,
@@ -441,6 +733,11 @@ pub enum TokenKind {
     Null,
     New,
     Object,
+
+    /// `operator`, which declares an operator overload such as `bool operator==(Foo a, Foo b)`.
+    ///
+    /// Not to be confused with [`TokenKind::Operator`], the kind of the operators themselves.
+    OperatorKw,
     Property,
     Public,
     Return,
@@ -469,21 +766,702 @@ pub enum TokenKind {
     RBracket,
     Comma,
     Qmark,
+    /// `:`
+    ///
+    /// Runs of colons are lexed greedily, so `:::` is [`Scope`](Self::Scope) followed by `:`.
     Colon,
+
+    /// `::`
+    ///
+    /// Runs of colons are lexed greedily, so `::::` is two [`Scope`](Self::Scope).
     Scope,
     Dot,
     Underscore,
 
+    /// `#!` line at the start of the input, only emitted with
+    /// [`LexerBuilder::allow_shebang`](crate::LexerBuilder::allow_shebang).
+    Shebang,
+
+    /// Spaces and tabs, only emitted with
+    /// [`LexerBuilder::emit_whitespace`](crate::LexerBuilder::emit_whitespace).
+    Whitespace,
+
+    /// Parameter of a macro, such as `%1`, only emitted in a `#define` directive. Elsewhere, `%`
+    /// is the modulo [operator](Operator::Percent).
+    MacroParam,
+
+    /// `enum struct`, never emitted by the lexer, which lexes an [Enum](TokenKind::Enum) and a
+    /// [Struct](TokenKind::Struct). See [`merge_adjacent`](crate::merge_adjacent).
+    EnumStruct,
+
+    /// Attribute such as `[[deprecated]]`, from the opening to the closing brackets, only emitted
+    /// with [`LexerBuilder::attribute_syntax`](crate::LexerBuilder::attribute_syntax).
+    Attribute,
+
     Unknown,
 
     /// End of file. This will always be the last token.
     Eof,
 }
 
+/// Defines [`KEYWORDS`], [`TokenKind::keyword_text`] and [`TokenKind::keyword_from_str`] from a
+/// single table, so that they cannot drift apart.
+macro_rules! keywords {
+    ($($kind:ident => $text:literal,)*) => {
+        /// Spellings of all the keywords recognized by the lexer, in the
+        /// [`Modern`](crate::Dialect::Modern) dialect.
+        ///
+        /// # Example
+        /// ```rust
+        /// use sourcepawn_lexer::KEYWORDS;
+        ///
+        /// assert!(KEYWORDS.contains(&"methodmap"));
+        /// ```
+        pub const KEYWORDS: &[&str] = &[$($text,)*];
+
+        impl TokenKind {
+            /// Returns the spelling of the kind if it is a keyword, [`None`] otherwise.
+            ///
+            /// This can be evaluated in a `const` context.
+            ///
+            /// # Example
+            /// ```rust
+            /// use sourcepawn_lexer::TokenKind;
+            ///
+            /// const IF: Option<&str> = TokenKind::If.keyword_text();
+            /// assert_eq!(IF, Some("if"));
+            /// ```
+            pub const fn keyword_text(self) -> Option<&'static str> {
+                let text = match self {
+                    $(Self::$kind => $text,)*
+                    _ => return None,
+                };
+
+                Some(text)
+            }
+
+            /// Returns the keyword kind spelled by the text, [`None`] if the text is not a keyword.
+            /// This is the inverse of [`TokenKind::keyword_text`].
+            ///
+            /// # Example
+            /// ```rust
+            /// use sourcepawn_lexer::TokenKind;
+            ///
+            /// assert_eq!(TokenKind::keyword_from_str("view_as"), Some(TokenKind::ViewAs));
+            /// assert_eq!(TokenKind::keyword_from_str("foo"), None);
+            /// ```
+            pub fn keyword_from_str(text: &str) -> Option<Self> {
+                let kind = match text {
+                    $($text => Self::$kind,)*
+                    _ => return None,
+                };
+
+                Some(kind)
+            }
+        }
+    };
+}
+
+keywords! {
+    Any => "any",
+    Bool => "bool",
+    Break => "break",
+    Case => "case",
+    Char => "char",
+    Class => "class",
+    Const => "const",
+    Continue => "continue",
+    Decl => "decl",
+    Default => "default",
+    Defined => "defined",
+    Delete => "delete",
+    Do => "do",
+    Else => "else",
+    Enum => "enum",
+    False => "false",
+    Float => "float",
+    OldFloat => "Float",
+    OldString => "String",
+    For => "for",
+    Forward => "forward",
+    Funcenum => "funcenum",
+    Functag => "functag",
+    Function => "function",
+    If => "if",
+    Int => "int",
+    InvalidFunction => "INVALID_FUNCTION",
+    Methodmap => "methodmap",
+    Native => "native",
+    Null => "null",
+    New => "new",
+    Object => "object",
+    OperatorKw => "operator",
+    Property => "property",
+    Public => "public",
+    Return => "return",
+    Sizeof => "sizeof",
+    Static => "static",
+    Stock => "stock",
+    Struct => "struct",
+    Switch => "switch",
+    This => "this",
+    True => "true",
+    Typedef => "typedef",
+    Typeset => "typeset",
+    Union => "union",
+    Using => "using",
+    ViewAs => "view_as",
+    Void => "void",
+    While => "while",
+    Nullable => "__nullable__",
+    Intrinsics => "__intrinsics__",
+}
+
+impl TokenKind {
+    /// Returns the discriminant of the variant of the kind.
+    ///
+    /// Kinds with a payload share the discriminant of their variant, for instance all the
+    /// [operators](TokenKind::Operator) have the same one.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{Operator, TokenKind};
+    ///
+    /// assert_eq!(TokenKind::Identifier.to_raw(), 0);
+    /// assert_eq!(
+    ///     TokenKind::Operator(Operator::Plus).to_raw(),
+    ///     TokenKind::Operator(Operator::Minus).to_raw()
+    /// );
+    /// ```
+    pub const fn to_raw(self) -> u16 {
+        // SAFETY: `TokenKind` is `repr(u16)`, so its layout starts with its `u16` discriminant.
+        unsafe { *(&self as *const Self as *const u16) }
+    }
+
+    /// Returns whether the kind is one of the given kinds.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{Operator, TokenKind};
+    ///
+    /// let kind = TokenKind::Operator(Operator::Plus);
+    /// assert!(kind.matches_any(&[TokenKind::Operator(Operator::Plus), TokenKind::Semicolon]));
+    /// ```
+    pub fn matches_any(&self, kinds: &[TokenKind]) -> bool {
+        kinds.contains(self)
+    }
+
+    /// Returns whether the kind is a keyword of the deprecated callback syntax, `functag` or
+    /// `funcenum`, which were replaced by `typedef` and `typeset`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::TokenKind;
+    ///
+    /// assert!(TokenKind::Funcenum.is_legacy_keyword());
+    /// assert!(!TokenKind::Typeset.is_legacy_keyword());
+    /// ```
+    pub fn is_legacy_keyword(&self) -> bool {
+        matches!(self, Self::Functag | Self::Funcenum)
+    }
+
+    /// Returns whether the kind is a keyword reserved for the compiler and its generated code,
+    /// `__intrinsics__` or `__nullable__`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::TokenKind;
+    ///
+    /// assert!(TokenKind::Intrinsics.is_compiler_intrinsic());
+    /// assert!(!TokenKind::Native.is_compiler_intrinsic());
+    /// ```
+    pub fn is_compiler_intrinsic(&self) -> bool {
+        matches!(self, Self::Intrinsics | Self::Nullable)
+    }
+
+    /// Returns whether the kind accesses a member, `.` or `::`, such as in `a.b.c` or `a::b`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::TokenKind;
+    ///
+    /// assert!(TokenKind::Dot.is_access_operator());
+    /// assert!(!TokenKind::Colon.is_access_operator());
+    /// ```
+    pub fn is_access_operator(&self) -> bool {
+        matches!(self, Self::Dot | Self::Scope)
+    }
+
+    /// Returns whether the kind is trivia, i.e whitespace, a newline, a line continuation, a comment
+    /// or a shebang.
+    ///
+    /// Trivia does not change the meaning of the code around it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{Comment, TokenKind};
+    ///
+    /// assert!(TokenKind::Comment(Comment::LineComment).is_trivia());
+    /// assert!(!TokenKind::Identifier.is_trivia());
+    /// ```
+    pub fn is_trivia(&self) -> bool {
+        matches!(
+            self,
+            Self::Whitespace
+                | Self::Newline
+                | Self::LineContinuation
+                | Self::Comment(_)
+                | Self::Shebang
+        )
+    }
+
+    /// Returns the text of the kind if all the tokens of this kind have the same text, [`None`]
+    /// otherwise.
+    ///
+    /// Identifiers, literals, comments, errors, shebangs, whitespace, macro parameters, attributes,
+    /// `#file`, `#leaving`, `#pragma`, `#include`, `#tryinclude`, `#error` and `#warning`
+    /// directives have a variable text, see [`Symbol::text_ref`](crate::Symbol::text_ref). The
+    /// text of newlines is `\n` and the text of line continuations is `\` followed by `\n`,
+    /// unless [`LexerBuilder::normalize_newlines`](crate::LexerBuilder::normalize_newlines) is
+    /// disabled.
+    ///
+    /// This can be evaluated in a `const` context.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::TokenKind;
+    ///
+    /// assert_eq!(TokenKind::Semicolon.static_text(), Some(";"));
+    /// assert_eq!(TokenKind::Identifier.static_text(), None);
+    /// ```
+    pub const fn static_text(self) -> Option<&'static str> {
+        let text = match self {
+            Self::Operator(op) => op.static_text(),
+            Self::PreprocDir(PreprocDir::MFile)
+            | Self::PreprocDir(PreprocDir::MLeaving)
+            | Self::PreprocDir(PreprocDir::MPragma)
+            | Self::PreprocDir(PreprocDir::MInclude)
+            | Self::PreprocDir(PreprocDir::MTryinclude)
+            | Self::PreprocDir(PreprocDir::MError)
+            | Self::PreprocDir(PreprocDir::MWarning)
+            | Self::Comment(_)
+            | Self::Literal(_)
+            | Self::Identifier
+            | Self::Error(_)
+            | Self::Shebang
+            | Self::Whitespace
+            | Self::MacroParam
+            | Self::EnumStruct
+            | Self::Attribute => return None,
+            Self::PreprocDir(dir) => return dir.static_text(),
+            Self::Newline => "\n",
+            Self::LineContinuation => "\\\n",
+            Self::Semicolon => ";",
+            Self::LBrace => "{",
+            Self::RBrace => "}",
+            Self::LParen => "(",
+            Self::RParen => ")",
+            Self::LBracket => "[",
+            Self::RBracket => "]",
+            Self::Comma => ",",
+            Self::Qmark => "?",
+            Self::Colon => ":",
+            Self::Scope => "::",
+            Self::Dot => ".",
+            Self::Unknown => "",
+            Self::Underscore => "_",
+            Self::Eof => "\0",
+            kind => return kind.keyword_text(),
+        };
+
+        Some(text)
+    }
+}
+
+/// Writes the spelling of the kind if all its tokens have the same text, such as `;` or `int`,
+/// and a label such as `identifier` otherwise.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{Operator, TokenKind};
+///
+/// assert_eq!(TokenKind::Operator(Operator::Plus).to_string(), "+");
+/// assert_eq!(TokenKind::Identifier.to_string(), "identifier");
+/// ```
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::Identifier => "identifier",
+            Self::Literal(literal) => return literal.fmt(f),
+            Self::Comment(comment) => return comment.fmt(f),
+            Self::PreprocDir(dir) => return dir.fmt(f),
+            Self::Error(error) => return error.fmt(f),
+            Self::Newline => "newline",
+            Self::LineContinuation => "line continuation",
+            Self::Shebang => "shebang",
+            Self::Whitespace => "whitespace",
+            Self::MacroParam => "macro parameter",
+            Self::EnumStruct => "enum struct",
+            Self::Attribute => "attribute",
+            Self::Unknown => "unknown token",
+            Self::Eof => "end of file",
+            kind => kind
+                .static_text()
+                .expect("the other kinds have a static text"),
+        };
+        f.write_str(label)
+    }
+}
+
+/// Defines the stable names of the variants of a kind, used to serialize [`TokenKind`], from a
+/// single table. The names are written out rather than derived from the variants, so that renaming
+/// a variant does not change them.
+macro_rules! stable_names {
+    ($ty:ident { $($variant:ident => $name:literal,)* }) => {
+        impl $ty {
+            const fn stable_name(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $name,)*
+                }
+            }
+
+            fn from_stable_name(name: &str) -> Option<Self> {
+                let kind = match name {
+                    $($name => Self::$variant,)*
+                    _ => return None,
+                };
+
+                Some(kind)
+            }
+        }
+    };
+    // Kinds with a payload are named by `TokenKind::kind_name`.
+    (@unit $ty:ident { $($variant:ident => $name:literal,)* }) => {
+        impl $ty {
+            const fn unit_stable_name(self) -> Option<&'static str> {
+                let name = match self {
+                    $(Self::$variant => $name,)*
+                    _ => return None,
+                };
+
+                Some(name)
+            }
+
+            fn unit_from_stable_name(name: &str) -> Option<Self> {
+                let kind = match name {
+                    $($name => Self::$variant,)*
+                    _ => return None,
+                };
+
+                Some(kind)
+            }
+        }
+    };
+}
+
+stable_names! {
+    Literal {
+        IntegerLiteral => "IntegerLiteral",
+        HexLiteral => "HexLiteral",
+        BinaryLiteral => "BinaryLiteral",
+        OctodecimalLiteral => "OctodecimalLiteral",
+        StringLiteral => "StringLiteral",
+        CharLiteral => "CharLiteral",
+        FloatLiteral => "FloatLiteral",
+        HexFloatLiteral => "HexFloatLiteral",
+    }
+}
+
+stable_names! {
+    Comment {
+        LineComment => "LineComment",
+        BlockComment => "BlockComment",
+        UnterminatedBlockComment => "UnterminatedBlockComment",
+    }
+}
+
+stable_names! {
+    Operator {
+        Ellipses => "Ellipses",
+        Plus => "Plus",
+        Minus => "Minus",
+        Star => "Star",
+        Slash => "Slash",
+        Stringize => "Stringize",
+        Percent => "Percent",
+        Ampersand => "Ampersand",
+        Bitor => "Bitor",
+        Bitxor => "Bitxor",
+        Shr => "Shr",
+        Ushr => "Ushr",
+        Shl => "Shl",
+        Assign => "Assign",
+        AssignAdd => "AssignAdd",
+        AssignSub => "AssignSub",
+        AssignMul => "AssignMul",
+        AssignDiv => "AssignDiv",
+        AssignMod => "AssignMod",
+        AssignBitAnd => "AssignBitAnd",
+        AssignBitOr => "AssignBitOr",
+        AssignBitXor => "AssignBitXor",
+        AssignShr => "AssignShr",
+        AssignUshr => "AssignUshr",
+        AssignShl => "AssignShl",
+        Increment => "Increment",
+        Decrement => "Decrement",
+        Equals => "Equals",
+        NotEquals => "NotEquals",
+        Lt => "Lt",
+        Le => "Le",
+        Gt => "Gt",
+        Ge => "Ge",
+        And => "And",
+        Or => "Or",
+        Not => "Not",
+        Tilde => "Tilde",
+    }
+}
+
+stable_names! {
+    PreprocDir {
+        MAssert => "MAssert",
+        MDefine => "MDefine",
+        MDeprecate => "MDeprecate",
+        MElse => "MElse",
+        MElseif => "MElseif",
+        MEndif => "MEndif",
+        MEndinput => "MEndinput",
+        MError => "MError",
+        MFile => "MFile",
+        MIf => "MIf",
+        MInclude => "MInclude",
+        MLeaving => "MLeaving",
+        MLine => "MLine",
+        MOptionalNewdecls => "MOptionalNewdecls",
+        MOptionalSemi => "MOptionalSemi",
+        MPragma => "MPragma",
+        MRequireNewdecls => "MRequireNewdecls",
+        MRequireSemi => "MRequireSemi",
+        MTryinclude => "MTryinclude",
+        MUndef => "MUndef",
+        MWarning => "MWarning",
+    }
+}
+
+stable_names! {
+    LexError {
+        TokenTooLong => "TokenTooLong",
+        UnterminatedString => "UnterminatedString",
+        InvalidUtf8 => "InvalidUtf8",
+        UnsupportedEncoding => "UnsupportedEncoding",
+        RedefinedMacro => "RedefinedMacro",
+        IncompleteLineContinuation => "IncompleteLineContinuation",
+        InvalidEscape => "InvalidEscape",
+        UnterminatedComment => "UnterminatedComment",
+        UnexpectedCharacter => "UnexpectedCharacter",
+        EmptyChar => "EmptyChar",
+    }
+}
+
+stable_names! {
+    @unit TokenKind {
+        Identifier => "Identifier",
+        Newline => "Newline",
+        LineContinuation => "LineContinuation",
+        Any => "Any",
+        Bool => "Bool",
+        Break => "Break",
+        Case => "Case",
+        Char => "Char",
+        Class => "Class",
+        Const => "Const",
+        Continue => "Continue",
+        Decl => "Decl",
+        Default => "Default",
+        Defined => "Defined",
+        Delete => "Delete",
+        Do => "Do",
+        Else => "Else",
+        Enum => "Enum",
+        False => "False",
+        Float => "Float",
+        OldFloat => "OldFloat",
+        OldString => "OldString",
+        For => "For",
+        Forward => "Forward",
+        Funcenum => "Funcenum",
+        Functag => "Functag",
+        Function => "Function",
+        If => "If",
+        Int => "Int",
+        InvalidFunction => "InvalidFunction",
+        Methodmap => "Methodmap",
+        Native => "Native",
+        Null => "Null",
+        New => "New",
+        Object => "Object",
+        OperatorKw => "OperatorKw",
+        Property => "Property",
+        Public => "Public",
+        Return => "Return",
+        Sizeof => "Sizeof",
+        Static => "Static",
+        Stock => "Stock",
+        Struct => "Struct",
+        Switch => "Switch",
+        This => "This",
+        True => "True",
+        Typedef => "Typedef",
+        Typeset => "Typeset",
+        Union => "Union",
+        Using => "Using",
+        ViewAs => "ViewAs",
+        Void => "Void",
+        While => "While",
+        Nullable => "Nullable",
+        Intrinsics => "Intrinsics",
+        Semicolon => "Semicolon",
+        LBrace => "LBrace",
+        RBrace => "RBrace",
+        LParen => "LParen",
+        RParen => "RParen",
+        LBracket => "LBracket",
+        RBracket => "RBracket",
+        Comma => "Comma",
+        Qmark => "Qmark",
+        Colon => "Colon",
+        Scope => "Scope",
+        Dot => "Dot",
+        Underscore => "Underscore",
+        Shebang => "Shebang",
+        Whitespace => "Whitespace",
+        MacroParam => "MacroParam",
+        EnumStruct => "EnumStruct",
+        Attribute => "Attribute",
+        Unknown => "Unknown",
+        Eof => "Eof",
+    }
+}
+
+impl TokenKind {
+    /// Returns the stable name of the kind, which is used to serialize it.
+    ///
+    /// The name of a kind without a payload is the name of its variant, such as `Identifier`, and
+    /// the name of a kind with a payload also contains the variant of its payload, such as
+    /// `Operator(Plus)`. Names are not affected by renames of the variants, so serialized token
+    /// streams can always be deserialized.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{Operator, TokenKind};
+    ///
+    /// assert_eq!(TokenKind::Operator(Operator::Plus).kind_name(), "Operator(Plus)");
+    /// ```
+    pub fn kind_name(self) -> SmolStr {
+        let (name, payload) = match self {
+            Self::Literal(literal) => ("Literal", literal.stable_name()),
+            Self::Comment(comment) => ("Comment", comment.stable_name()),
+            Self::Operator(op) => ("Operator", op.stable_name()),
+            Self::PreprocDir(dir) => ("PreprocDir", dir.stable_name()),
+            Self::Error(error) => ("Error", error.stable_name()),
+            kind => {
+                return kind
+                    .unit_stable_name()
+                    .expect("kinds without a payload have a stable name")
+                    .into()
+            }
+        };
+
+        SmolStr::from(format!("{name}({payload})"))
+    }
+
+    /// Returns the kind with the given [stable name](TokenKind::kind_name), [`None`] if there is
+    /// no such kind.
+    ///
+    /// The former name of [`Operator::AssignUshr`], `Operator(AssignUshl)`, is also accepted.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sourcepawn_lexer::{Operator, TokenKind};
+    ///
+    /// assert_eq!(
+    ///     TokenKind::from_kind_name("Operator(Plus)"),
+    ///     Some(TokenKind::Operator(Operator::Plus))
+    /// );
+    /// ```
+    pub fn from_kind_name(name: &str) -> Option<Self> {
+        let Some((name, payload)) = name.strip_suffix(')').and_then(|name| name.split_once('('))
+        else {
+            return Self::unit_from_stable_name(name);
+        };
+        match name {
+            "Literal" => Literal::from_stable_name(payload).map(Self::Literal),
+            "Comment" => Comment::from_stable_name(payload).map(Self::Comment),
+            "Operator" => {
+                let payload = match payload {
+                    "AssignUshl" => "AssignUshr",
+                    payload => payload,
+                };
+                Operator::from_stable_name(payload).map(Self::Operator)
+            }
+            "PreprocDir" => PreprocDir::from_stable_name(payload).map(Self::PreprocDir),
+            "Error" => LexError::from_stable_name(payload).map(Self::Error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for TokenKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.kind_name())
+    }
+}
+
+/// Deserializes a [`TokenKind`] from its [stable name](TokenKind::kind_name), or from the map
+/// which was used before stable names, such as `{"Operator": "Plus"}`.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for TokenKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(TokenKindVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct TokenKindVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> de::Visitor<'de> for TokenKindVisitor {
+    type Value = TokenKind;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("the name of a token kind")
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<TokenKind, E> {
+        TokenKind::from_kind_name(name)
+            .ok_or_else(|| E::custom(format!("unknown token kind `{name}`")))
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<TokenKind, A::Error> {
+        let Some((name, payload)) = map.next_entry::<String, String>()? else {
+            return Err(de::Error::custom(
+                "expected a token kind, found an empty map",
+            ));
+        };
+        if map.next_key::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::custom(
+                "expected a token kind, found several entries",
+            ));
+        }
+        self.visit_str(&format!("{name}({payload})"))
+    }
+}
+
 impl TryFrom<Token> for TokenKind {
     type Error = &'static str;
 
-    fn try_from(token: Token) -> Result<Self, Self::Error> {
+    fn try_from(token: Token) -> Result<Self, &'static str> {
         let token_kind = match token {
             Token::Identifier => TokenKind::Identifier,
             Token::IntegerLiteral => TokenKind::Literal(Literal::IntegerLiteral),
@@ -491,12 +1469,15 @@ impl TryFrom<Token> for TokenKind {
             Token::BinaryLiteral => TokenKind::Literal(Literal::BinaryLiteral),
             Token::OctodecimalLiteral => TokenKind::Literal(Literal::OctodecimalLiteral),
             Token::StringLiteral => TokenKind::Literal(Literal::StringLiteral),
+            Token::UnterminatedStringLiteral => TokenKind::Error(LexError::UnterminatedString),
             Token::CharLiteral => TokenKind::Literal(Literal::CharLiteral),
             Token::FloatLiteral => TokenKind::Literal(Literal::FloatLiteral),
+            Token::HexFloatLiteral => TokenKind::Literal(Literal::HexFloatLiteral),
             Token::Newline => TokenKind::Newline,
             Token::LineContinuation => TokenKind::LineContinuation,
             Token::LineComment => TokenKind::Comment(Comment::LineComment),
             Token::BlockComment => TokenKind::Comment(Comment::BlockComment),
+            Token::Any => TokenKind::Any,
             Token::Bool => TokenKind::Bool,
             Token::Break => TokenKind::Break,
             Token::Case => TokenKind::Case,
@@ -517,6 +1498,7 @@ impl TryFrom<Token> for TokenKind {
             Token::OldString => TokenKind::OldString,
             Token::For => TokenKind::For,
             Token::Forward => TokenKind::Forward,
+            Token::Funcenum => TokenKind::Funcenum,
             Token::Functag => TokenKind::Functag,
             Token::Function => TokenKind::Function,
             Token::If => TokenKind::If,
@@ -527,6 +1509,7 @@ impl TryFrom<Token> for TokenKind {
             Token::Null => TokenKind::Null,
             Token::New => TokenKind::New,
             Token::Object => TokenKind::Object,
+            Token::OperatorKw => TokenKind::OperatorKw,
             Token::Property => TokenKind::Property,
             Token::Public => TokenKind::Public,
             Token::Return => TokenKind::Return,
@@ -545,6 +1528,7 @@ impl TryFrom<Token> for TokenKind {
             Token::Void => TokenKind::Void,
             Token::While => TokenKind::While,
             Token::Nullable => TokenKind::Nullable,
+            Token::MAssert => TokenKind::PreprocDir(PreprocDir::MAssert),
             Token::MDefine => TokenKind::PreprocDir(PreprocDir::MDefine),
             Token::MDeprecate => TokenKind::PreprocDir(PreprocDir::MDeprecate),
             Token::MElse => TokenKind::PreprocDir(PreprocDir::MElse),
@@ -562,6 +1546,8 @@ impl TryFrom<Token> for TokenKind {
             Token::MRequireNewdecls => TokenKind::PreprocDir(PreprocDir::MRequireNewdecls),
             Token::MRequireSemi => TokenKind::PreprocDir(PreprocDir::MRequireSemi),
             Token::MTryinclude => TokenKind::PreprocDir(PreprocDir::MTryinclude),
+            Token::MError => TokenKind::PreprocDir(PreprocDir::MError),
+            Token::MWarning => TokenKind::PreprocDir(PreprocDir::MWarning),
             Token::MUndef => TokenKind::PreprocDir(PreprocDir::MUndef),
             Token::Intrinsics => TokenKind::Intrinsics,
             Token::Ellipses => TokenKind::Operator(Operator::Ellipses),
@@ -594,7 +1580,7 @@ impl TryFrom<Token> for TokenKind {
             Token::AssignBitOr => TokenKind::Operator(Operator::AssignBitOr),
             Token::AssignBitXor => TokenKind::Operator(Operator::AssignBitXor),
             Token::AssignShr => TokenKind::Operator(Operator::AssignShr),
-            Token::AssignUshl => TokenKind::Operator(Operator::AssignUshl),
+            Token::AssignUshr => TokenKind::Operator(Operator::AssignUshr),
             Token::AssignShl => TokenKind::Operator(Operator::AssignShl),
             Token::Increment => TokenKind::Operator(Operator::Increment),
             Token::Decrement => TokenKind::Operator(Operator::Decrement),
@@ -614,6 +1600,7 @@ impl TryFrom<Token> for TokenKind {
             Token::Scope => TokenKind::Scope,
             Token::Dot => TokenKind::Dot,
             Token::Underscore => TokenKind::Underscore,
+            Token::Whitespace => TokenKind::Whitespace,
             Token::Unknown => TokenKind::Unknown,
         };
 
@@ -0,0 +1,134 @@
+use crate::TokenKind;
+
+/// Number of words for the kinds without a payload, indexed by [`TokenKind::to_raw`].
+const UNIT_WORDS: usize = 2;
+
+/// A set of [`TokenKind`], stored as a bitset.
+///
+/// Sets can be built in a `const` context, which makes them suited for the FIRST and FOLLOW sets
+/// of a parser.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{TokenKind, TokenSet};
+///
+/// const BLOCK: TokenSet = TokenSet::new(&[TokenKind::LBrace, TokenKind::RBrace]);
+/// assert!(BLOCK.contains(TokenKind::LBrace));
+/// assert!(!BLOCK.contains(TokenKind::Semicolon));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TokenSet {
+    /// One word for each variant with a payload, after the words of the kinds without a payload.
+    words: [u64; UNIT_WORDS + 5],
+}
+
+impl TokenSet {
+    /// The empty set.
+    pub const EMPTY: TokenSet = TokenSet {
+        words: [0; UNIT_WORDS + 5],
+    };
+
+    /// Keywords which are types.
+    pub const TYPE_KEYWORDS: TokenSet = TokenSet::new(&[
+        TokenKind::Any,
+        TokenKind::Bool,
+        TokenKind::Char,
+        TokenKind::Float,
+        TokenKind::Int,
+        TokenKind::Object,
+        TokenKind::Void,
+        TokenKind::OldFloat,
+        TokenKind::OldString,
+    ]);
+
+    /// Kinds which start a statement other than a declaration or an expression statement.
+    pub const STATEMENT_START: TokenSet = TokenSet::new(&[
+        TokenKind::If,
+        TokenKind::For,
+        TokenKind::While,
+        TokenKind::Do,
+        TokenKind::Switch,
+        TokenKind::Return,
+        TokenKind::Break,
+        TokenKind::Continue,
+        TokenKind::Delete,
+        TokenKind::LBrace,
+        TokenKind::Semicolon,
+    ]);
+
+    /// Creates a set which contains the given kinds.
+    pub const fn new(kinds: &[TokenKind]) -> Self {
+        let mut set = Self::EMPTY;
+        let mut i = 0;
+        while i < kinds.len() {
+            set = set.with(kinds[i]);
+            i += 1;
+        }
+
+        set
+    }
+
+    /// Returns a copy of the set which also contains the kind.
+    pub const fn with(mut self, kind: TokenKind) -> Self {
+        let (word, bit) = position(kind);
+        self.words[word] |= 1 << bit;
+        self
+    }
+
+    /// Adds the kind to the set.
+    pub fn insert(&mut self, kind: TokenKind) {
+        *self = self.with(kind);
+    }
+
+    /// Returns the set of the kinds which are in either set.
+    pub const fn union(mut self, other: TokenSet) -> Self {
+        let mut i = 0;
+        while i < self.words.len() {
+            self.words[i] |= other.words[i];
+            i += 1;
+        }
+
+        self
+    }
+
+    /// Returns whether the set contains the kind.
+    pub const fn contains(&self, kind: TokenKind) -> bool {
+        let (word, bit) = position(kind);
+        self.words[word] & (1 << bit) != 0
+    }
+
+    /// Returns whether the set is empty.
+    pub const fn is_empty(&self) -> bool {
+        let mut i = 0;
+        while i < self.words.len() {
+            if self.words[i] != 0 {
+                return false;
+            }
+            i += 1;
+        }
+
+        true
+    }
+}
+
+/// Returns the word and the bit of the kind in the set.
+const fn position(kind: TokenKind) -> (usize, u32) {
+    let (word, index) = match kind {
+        TokenKind::Literal(literal) => (UNIT_WORDS, literal as u32),
+        TokenKind::Comment(comment) => (UNIT_WORDS + 1, comment as u32),
+        TokenKind::Operator(operator) => (UNIT_WORDS + 2, operator as u32),
+        TokenKind::PreprocDir(dir) => (UNIT_WORDS + 3, dir as u32),
+        TokenKind::Error(error) => (UNIT_WORDS + 4, error as u32),
+        _ => {
+            let raw = kind.to_raw() as u32;
+            assert!(
+                raw < UNIT_WORDS as u32 * u64::BITS,
+                "too many variants for TokenSet"
+            );
+            ((raw / u64::BITS) as usize, raw % u64::BITS)
+        }
+    };
+    assert!(index < u64::BITS, "too many variants for TokenSet");
+
+    (word, index)
+}
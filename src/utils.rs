@@ -0,0 +1,483 @@
+use std::ops::Range;
+
+use text_size::{TextRange, TextSize};
+
+use crate::{Delta, Dialect, Modifier, ModifierSet, Symbol, TokenKind, TokenSet};
+
+/// Splits a slice of symbols into logical lines.
+///
+/// Each line ends with its [Newline](TokenKind::Newline) token, if any. A
+/// [LineContinuation](TokenKind::LineContinuation) does not end a line, so a continued statement or
+/// preprocessor directive stays in a single group. The last line does not need to end with a
+/// newline, and an [Eof](TokenKind::Eof) token belongs to the last line.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{group_by_line, SourcepawnLexer};
+///
+/// let symbols: Vec<_> = SourcepawnLexer::new("int foo;\nint bar;").collect();
+/// assert_eq!(group_by_line(&symbols).len(), 2);
+/// ```
+pub fn group_by_line(symbols: &[Symbol]) -> Vec<&[Symbol]> {
+    symbols
+        .split_inclusive(|symbol| symbol.token_kind == TokenKind::Newline)
+        .collect()
+}
+
+/// Returns the ranges of the blocks delimited by matching braces, for folding.
+///
+/// Each range starts at a [LBrace](TokenKind::LBrace) and ends after the matching
+/// [RBrace](TokenKind::RBrace), braces included. Braces are matched by nesting, and the ranges are
+/// sorted by start, so that a block comes before the blocks nested in it. A closing brace without
+/// an opening brace is ignored, and so is an opening brace which is never closed.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{brace_fold_ranges, SourcepawnLexer, TextRange};
+///
+/// let symbols: Vec<_> = SourcepawnLexer::new("void foo() { if (bar) { } }").collect();
+/// assert_eq!(
+///     brace_fold_ranges(&symbols),
+///     vec![
+///         TextRange::new(11.into(), 27.into()),
+///         TextRange::new(22.into(), 25.into())
+///     ]
+/// );
+/// ```
+pub fn brace_fold_ranges(symbols: &[Symbol]) -> Vec<TextRange> {
+    let mut open_braces = vec![];
+    let mut ranges = vec![];
+    for symbol in symbols {
+        match symbol.token_kind {
+            TokenKind::LBrace => open_braces.push(symbol.range.start()),
+            TokenKind::RBrace => {
+                if let Some(start) = open_braces.pop() {
+                    ranges.push(TextRange::new(start, symbol.range.end()));
+                }
+            }
+            _ => (),
+        }
+    }
+    ranges.sort_by_key(|range| range.start());
+
+    ranges
+}
+
+/// Returns the ranges of the indentations which mix tabs and spaces.
+///
+/// An indentation is a [Whitespace](TokenKind::Whitespace) symbol at the start of a line, so the
+/// symbols must be lexed with [`LexerBuilder::emit_whitespace`](crate::LexerBuilder::emit_whitespace),
+/// otherwise nothing is reported. The whitespace after a
+/// [LineContinuation](TokenKind::LineContinuation) also starts a line.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{detect_mixed_indentation, LexerBuilder, TextRange};
+///
+/// let symbols: Vec<_> = LexerBuilder::new()
+///     .emit_whitespace(true)
+///     .build("int foo;\n\t  int bar;")
+///     .collect();
+/// assert_eq!(
+///     detect_mixed_indentation(&symbols),
+///     vec![TextRange::new(9.into(), 12.into())]
+/// );
+/// ```
+pub fn detect_mixed_indentation(symbols: &[Symbol]) -> Vec<TextRange> {
+    let mut line_start = true;
+    let mut ranges = vec![];
+    for symbol in symbols {
+        if line_start && symbol.token_kind == TokenKind::Whitespace {
+            let text = symbol.text();
+            if text.contains('\t') && text.contains(' ') {
+                ranges.push(symbol.range);
+            }
+        }
+        line_start = matches!(
+            symbol.token_kind,
+            TokenKind::Newline | TokenKind::LineContinuation
+        );
+    }
+
+    ranges
+}
+
+/// Returns the [modifiers](Modifier) of the declaration whose name follows `window`, the symbols
+/// before the name.
+///
+/// The modifier keywords, such as `const` or `static`, are collected going backwards from the end
+/// of the window. Trivia, type keywords, identifiers which could be tags or types such as `Handle`,
+/// the brackets of array types and the `:` of old-style tags are skipped, and any other symbol
+/// stops the search.
+///
+/// This is only a lexical hint, which does not resolve declarations: in `const int a, b`, the
+/// comma stops the search so `b` has no modifiers, and any identifier preceded by a modifier
+/// keyword gets its modifiers, even if it is not a declaration.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{infer_modifiers, Modifier, SourcepawnLexer};
+///
+/// let symbols: Vec<_> = SourcepawnLexer::new("static const int foo = 1;").collect();
+/// let modifiers = infer_modifiers(&symbols[..3]);
+/// assert!(modifiers.contains(Modifier::Static));
+/// assert!(modifiers.contains(Modifier::Const));
+/// ```
+pub fn infer_modifiers(window: &[Symbol]) -> ModifierSet {
+    let mut modifiers = ModifierSet::EMPTY;
+    for symbol in window.iter().rev() {
+        let kind = symbol.token_kind;
+        if let Some(modifier) = Modifier::from_kind(kind) {
+            modifiers.insert(modifier);
+        } else if !(kind.is_trivia()
+            || TokenSet::TYPE_KEYWORDS.contains(kind)
+            || matches!(
+                kind,
+                TokenKind::Identifier
+                    | TokenKind::LBracket
+                    | TokenKind::RBracket
+                    | TokenKind::Colon
+            ))
+        {
+            break;
+        }
+    }
+
+    modifiers
+}
+
+/// Returns whether the last symbol of `window` is a `;` which separates the clauses of a `for`
+/// loop header, rather than a statement terminator.
+///
+/// This depends on the context: `window` must contain the symbols before the `;`, at least up to
+/// the `for` keyword. The `;` is a separator if it is inside parentheses whose `(` follows `for`.
+/// The search stops at a brace which is not nested in parentheses.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{is_for_clause_separator, SourcepawnLexer};
+///
+/// let symbols: Vec<_> = SourcepawnLexer::new("for (;;) {}").collect();
+/// assert!(is_for_clause_separator(&symbols[..3]));
+/// ```
+pub fn is_for_clause_separator(window: &[Symbol]) -> bool {
+    let Some((last, rest)) = window.split_last() else {
+        return false;
+    };
+    if last.token_kind != TokenKind::Semicolon {
+        return false;
+    }
+    let mut depth = 0u32;
+    for (idx, symbol) in rest.iter().enumerate().rev() {
+        match symbol.token_kind {
+            TokenKind::RParen => depth += 1,
+            TokenKind::LParen if depth > 0 => depth -= 1,
+            TokenKind::LParen => {
+                return rest[..idx]
+                    .iter()
+                    .rev()
+                    .find(|symbol| !symbol.token_kind.is_trivia())
+                    .is_some_and(|symbol| symbol.token_kind == TokenKind::For);
+            }
+            TokenKind::LBrace | TokenKind::RBrace if depth == 0 => return false,
+            _ => (),
+        }
+    }
+
+    false
+}
+
+/// Replaces the sequences of consecutive kinds given by `rules` with a single symbol of the
+/// merged kind, such as [Enum](TokenKind::Enum) and [Struct](TokenKind::Struct) with
+/// [EnumStruct](TokenKind::EnumStruct).
+///
+/// Each rule is a sequence of kinds and the kind of the merged symbol. The symbols of a sequence
+/// must be consecutive in the slice, so a comment or a newline between them prevents the merge.
+/// The rules are tried in order at each position, and the first one which matches is applied.
+///
+/// The merged symbol covers the range of the whole sequence, and keeps the delta of its first
+/// symbol. Its text is the text of the sequence, where the gaps between the symbols are filled
+/// with spaces, unless the merged kind has a [static text](TokenKind::static_text).
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{merge_adjacent, SourcepawnLexer, TokenKind};
+///
+/// let symbols: Vec<_> = SourcepawnLexer::new("enum struct Foo").collect();
+/// let merged = merge_adjacent(
+///     &symbols,
+///     &[(&[TokenKind::Enum, TokenKind::Struct], TokenKind::EnumStruct)],
+/// );
+/// assert_eq!(merged[0].token_kind, TokenKind::EnumStruct);
+/// assert_eq!(merged[0].text(), "enum struct");
+/// ```
+pub fn merge_adjacent(symbols: &[Symbol], rules: &[(&[TokenKind], TokenKind)]) -> Vec<Symbol> {
+    let mut merged = vec![];
+    let mut rest = symbols;
+    while let Some(first) = rest.first() {
+        let rule = rules.iter().find(|(kinds, _)| {
+            !kinds.is_empty()
+                && rest.len() >= kinds.len()
+                && rest
+                    .iter()
+                    .zip(kinds.iter())
+                    .all(|(symbol, kind)| symbol.token_kind == *kind)
+        });
+        let Some((kinds, kind)) = rule else {
+            merged.push(first.clone());
+            rest = &rest[1..];
+            continue;
+        };
+        let (sequence, tail) = rest.split_at(kinds.len());
+        let mut text = String::new();
+        let mut end = first.range.start();
+        for symbol in sequence {
+            end = push_with_gap(&mut text, end, symbol);
+        }
+        // Synthetic symbols may be inserted at different offsets, covering their ranges would
+        // give the merged symbol a text in the source.
+        let synthetic = sequence.iter().all(Symbol::is_synthetic);
+        let range = if synthetic {
+            TextRange::empty(first.range.start())
+        } else {
+            first.range.cover(sequence[sequence.len() - 1].range)
+        };
+        let mut symbol = Symbol::new(*kind, Some(&text), range, first.delta);
+        symbol.in_preprocessor = first.in_preprocessor;
+        debug_assert!(
+            !synthetic || symbol.is_synthetic(),
+            "a synthetic symbol must have an empty range"
+        );
+        merged.push(symbol);
+        rest = tail;
+    }
+
+    merged
+}
+
+/// Returns the text of `source` from the start of `start` to the end of `end`, both symbols
+/// included.
+///
+/// Returns an empty string if `start` is after `end`, or if the range does not fit in `source`.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{text_between, SourcepawnLexer};
+///
+/// let input = "foo(1, 2);";
+/// let symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+/// assert_eq!(text_between(input, &symbols[1], &symbols[5]), "(1, 2)");
+/// ```
+pub fn text_between<'a>(source: &'a str, start: &Symbol, end: &Symbol) -> &'a str {
+    slice(source, start.range.start(), end.range.end())
+}
+
+/// Returns the text of `source` between the end of `start` and the start of `end`, both symbols
+/// excluded.
+///
+/// Returns an empty string if `start` ends after the start of `end`, or if the range does not fit
+/// in `source`.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{text_between_exclusive, SourcepawnLexer};
+///
+/// let input = "foo(1, 2);";
+/// let symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+/// assert_eq!(text_between_exclusive(input, &symbols[1], &symbols[5]), "1, 2");
+/// ```
+pub fn text_between_exclusive<'a>(source: &'a str, start: &Symbol, end: &Symbol) -> &'a str {
+    slice(source, start.range.end(), end.range.start())
+}
+
+fn slice(source: &str, start: TextSize, end: TextSize) -> &str {
+    if start > end {
+        return "";
+    }
+
+    source
+        .get(Range::<usize>::from(TextRange::new(start, end)))
+        .unwrap_or_default()
+}
+
+/// Reclassifies the keywords of a slice of symbols for a [`Dialect`].
+///
+/// An [Identifier](TokenKind::Identifier) whose text is a keyword of the dialect is promoted to
+/// that keyword, and a keyword which does not exist in the dialect is demoted to an identifier.
+/// This gives the same kinds as lexing the input with
+/// [`LexerBuilder::dialect`](crate::LexerBuilder::dialect), for symbols which were lexed with
+/// another dialect.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{promote_soft_keywords, Dialect, LexerBuilder, TokenKind};
+///
+/// let mut symbols: Vec<_> = LexerBuilder::new()
+///     .dialect(Dialect::Legacy)
+///     .build("methodmap Foo")
+///     .collect();
+/// assert_eq!(symbols[0].token_kind, TokenKind::Identifier);
+/// promote_soft_keywords(&mut symbols, Dialect::Modern);
+/// assert_eq!(symbols[0].token_kind, TokenKind::Methodmap);
+/// ```
+pub fn promote_soft_keywords(symbols: &mut [Symbol], dialect: Dialect) {
+    for symbol in symbols {
+        if symbol.token_kind == TokenKind::Identifier {
+            let Some(keyword) = symbol.as_identifier().and_then(TokenKind::keyword_from_str) else {
+                continue;
+            };
+            if dialect.has_keyword(keyword) {
+                let (in_preprocessor, synthetic) = (symbol.in_preprocessor, symbol.is_synthetic());
+                *symbol = Symbol::new(keyword, None, symbol.range, symbol.delta);
+                symbol.in_preprocessor = in_preprocessor;
+                debug_assert_eq!(symbol.is_synthetic(), synthetic);
+            }
+        } else if let Some(text) = symbol.token_kind.keyword_text() {
+            if !dialect.has_keyword(symbol.token_kind) {
+                let (in_preprocessor, synthetic) = (symbol.in_preprocessor, symbol.is_synthetic());
+                *symbol = Symbol::new(
+                    TokenKind::Identifier,
+                    Some(text),
+                    symbol.range,
+                    symbol.delta,
+                );
+                symbol.in_preprocessor = in_preprocessor;
+                debug_assert_eq!(symbol.is_synthetic(), synthetic);
+            }
+        }
+    }
+}
+
+/// Difference between two lexings of the same document, see [`token_diff`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TokenDiff {
+    /// Number of symbols at the start of both slices which did not change.
+    pub unchanged_prefix: usize,
+
+    /// Range of the changed symbols in the new slice.
+    ///
+    /// The symbols they replace are `old[unchanged_prefix..old.len() - unchanged_suffix]`.
+    pub changed: Range<usize>,
+
+    /// Number of symbols at the end of both slices which did not change.
+    pub unchanged_suffix: usize,
+}
+
+/// Compares the symbols of a document before and after an edit, and returns the part that changed.
+///
+/// Symbols are compared with [`Symbol::eq_ignoring_position`], so that the symbols after an edit
+/// are unchanged even though their ranges moved. A [synthetic](Symbol::is_synthetic) symbol is
+/// only equal to another synthetic symbol, so inserting or removing one is a change even if a
+/// lexed symbol with the same text is next to it. The unchanged prefix and suffix never overlap.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{token_diff, SourcepawnLexer};
+///
+/// let old: Vec<_> = SourcepawnLexer::new("int foo = 1;").collect();
+/// let new: Vec<_> = SourcepawnLexer::new("int foo = 10 + 2;").collect();
+/// let diff = token_diff(&old, &new);
+/// assert_eq!(diff.unchanged_prefix, 3);
+/// assert_eq!(diff.changed, 3..6);
+/// assert_eq!(diff.unchanged_suffix, 2);
+/// ```
+pub fn token_diff(old: &[Symbol], new: &[Symbol]) -> TokenDiff {
+    let unchanged = |(old, new): &(&Symbol, &Symbol)| {
+        old.eq_ignoring_position(new) && old.is_synthetic() == new.is_synthetic()
+    };
+    let unchanged_prefix = old.iter().zip(new).take_while(unchanged).count();
+    let unchanged_suffix = old[unchanged_prefix..]
+        .iter()
+        .rev()
+        .zip(new[unchanged_prefix..].iter().rev())
+        .take_while(unchanged)
+        .count();
+
+    TokenDiff {
+        unchanged_prefix,
+        changed: unchanged_prefix..new.len() - unchanged_suffix,
+        unchanged_suffix,
+    }
+}
+
+/// Rebuilds the source text from the ranges and the texts of the symbols.
+///
+/// Each symbol is written at the start of its range, and the gaps between the symbols, i.e the
+/// whitespace which was not emitted, are filled with spaces. The text is exact when the symbols are
+/// lexed with [`LexerBuilder::emit_whitespace`](crate::LexerBuilder::emit_whitespace) and without
+/// [`LexerBuilder::normalize_newlines`](crate::LexerBuilder::normalize_newlines).
+///
+/// [Synthetic](Symbol::is_synthetic) symbols and the [Eof](TokenKind::Eof) token have no text in
+/// the source and are skipped, wherever they are in the slice. A symbol which starts before the
+/// end of the previous one is written right after it.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{reconstruct, SourcepawnLexer};
+///
+/// let symbols: Vec<_> = SourcepawnLexer::new("int  foo;").collect();
+/// assert_eq!(reconstruct(&symbols), "int  foo;");
+/// ```
+pub fn reconstruct(symbols: &[Symbol]) -> String {
+    let mut text = String::new();
+    let mut end = TextSize::from(0);
+    for symbol in symbols {
+        if symbol.range.is_empty() {
+            continue;
+        }
+        end = push_with_gap(&mut text, end, symbol);
+    }
+
+    text
+}
+
+/// Pushes the text of `symbol` to `text`, after a space for each byte between `end` and the start
+/// of the symbol, and returns the new end of the text.
+fn push_with_gap(text: &mut String, end: TextSize, symbol: &Symbol) -> TextSize {
+    if symbol.range.start() > end {
+        let gap = u32::from(symbol.range.start() - end) as usize;
+        text.push_str(&" ".repeat(gap));
+    }
+    text.push_str(&symbol.text());
+    end.max(symbol.range.end())
+}
+
+/// Converts a sequence of [deltas](Delta) and lengths back into absolute ranges.
+///
+/// This is the inverse of the delta computation of the lexer: the start of a token is the end
+/// of the previous token plus its delta, and the start of the first token is the initial offset
+/// of the cursor, since the delta of the first token is always 0. Deltas computed with
+/// [`LexerBuilder::line_relative_deltas`](crate::LexerBuilder::line_relative_deltas) are not
+/// supported.
+///
+/// # Example
+/// ```rust
+/// use sourcepawn_lexer::{DeltaCursor, SourcepawnLexer, TextLen};
+///
+/// let mut cursor = DeltaCursor::new(0.into());
+/// for symbol in SourcepawnLexer::new("int foo = 1;") {
+///     assert_eq!(cursor.next_range(symbol.delta, symbol.range.len()), symbol.range);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeltaCursor {
+    prev_end: TextSize,
+}
+
+impl DeltaCursor {
+    /// Creates a cursor whose first range starts at `initial_offset`.
+    pub fn new(initial_offset: TextSize) -> Self {
+        Self {
+            prev_end: initial_offset,
+        }
+    }
+
+    /// Returns the range of the next token, given its delta and its length.
+    pub fn next_range(&mut self, delta: Delta, len: TextSize) -> TextRange {
+        let start = (u32::from(self.prev_end) as i64 + delta as i64) as u32;
+        let range = TextRange::at(start.into(), len);
+        self.prev_end = range.end();
+
+        range
+    }
+}
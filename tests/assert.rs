@@ -0,0 +1,41 @@
+mod utils;
+
+use insta::assert_json_snapshot;
+use sourcepawn_lexer::*;
+use utils::collect_tokens;
+
+#[test]
+fn assert_simple() {
+    let input = r#"#assert sizeof(x) == 4
+int foo;
+"#;
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_json_snapshot!(collect_tokens(&mut lexer));
+}
+
+#[test]
+fn assert_line_continuation() {
+    let input = "#assert FOO \\\n    == 4\nint foo;";
+
+    let mut lexer = SourcepawnLexer::new(input);
+    let mut in_preprocessor = Vec::new();
+    while let Some(symbol) = lexer.next() {
+        in_preprocessor.push((symbol.text().to_string(), lexer.in_preprocessor()));
+    }
+    assert_eq!(
+        in_preprocessor,
+        vec![
+            ("#assert".to_string(), true),
+            ("FOO".to_string(), true),
+            ("\\\n".to_string(), true),
+            ("==".to_string(), true),
+            ("4".to_string(), true),
+            ("\n".to_string(), false),
+            ("int".to_string(), false),
+            ("foo".to_string(), false),
+            (";".to_string(), false),
+            ("\0".to_string(), false),
+        ]
+    );
+}
@@ -0,0 +1,362 @@
+use sourcepawn_lexer::*;
+
+#[test]
+fn max_token_len_block_comment() {
+    let input = format!("/*{}*/\nint foo;", "a".repeat(64));
+
+    let lexer = LexerBuilder::new().max_token_len(16).build(&input);
+    let symbols: Vec<_> = lexer.collect();
+    assert_eq!(
+        symbols[0].token_kind,
+        TokenKind::Error(LexError::TokenTooLong)
+    );
+    assert_eq!(symbols[0].range, TextRange::new(0.into(), 16.into()));
+    assert_eq!(symbols.last().unwrap().token_kind, TokenKind::Eof);
+}
+
+#[test]
+fn max_token_len_pragma() {
+    let input = format!("#pragma deprecated {}\nint foo;", "a".repeat(64));
+
+    let lexer = LexerBuilder::new().max_token_len(16).build(&input);
+    let symbols: Vec<_> = lexer.collect();
+    assert_eq!(
+        symbols[0].token_kind,
+        TokenKind::Error(LexError::TokenTooLong)
+    );
+    assert_eq!(symbols.last().unwrap().token_kind, TokenKind::Eof);
+}
+
+#[test]
+fn max_token_len_within_limit() {
+    let input = "/* foo */\nint foo;";
+
+    let lexer = LexerBuilder::new().max_token_len(16).build(input);
+    let symbols: Vec<_> = lexer.collect();
+    assert_eq!(
+        symbols[0].token_kind,
+        TokenKind::Comment(Comment::BlockComment)
+    );
+    assert_eq!(symbols[0].text(), "/* foo */");
+}
+
+#[test]
+fn max_token_len_block_comment_boundary() {
+    let input = format!("/*{}*/", "a".repeat(12));
+
+    let symbol = LexerBuilder::new().max_token_len(16).build(&input).next();
+    assert_eq!(
+        symbol.unwrap().token_kind,
+        TokenKind::Comment(Comment::BlockComment)
+    );
+    let symbol = LexerBuilder::new().max_token_len(15).build(&input).next();
+    assert_eq!(
+        symbol.unwrap().token_kind,
+        TokenKind::Error(LexError::TokenTooLong)
+    );
+}
+
+#[test]
+fn line_relative_deltas() {
+    let input = "  int foo;\n\tfoo = 1; /* a\n  b */ foo++;\n    return;";
+
+    let deltas = |line_relative_deltas| {
+        LexerBuilder::new()
+            .line_relative_deltas(line_relative_deltas)
+            .build(input)
+            .map(|symbol| (symbol.text().to_string(), symbol.delta))
+            .collect::<Vec<_>>()
+    };
+    let default = deltas(false);
+    let line_relative = deltas(true);
+
+    assert_eq!(default[0], ("int".to_string(), 0));
+    assert_eq!(line_relative[0], ("int".to_string(), 2));
+    // All the other tokens are either first on their line after a newline token, or
+    // follow a token which ends on their line, so both modes agree.
+    assert_eq!(default[1..], line_relative[1..]);
+    let foo_after_comment = line_relative
+        .iter()
+        .position(|(text, _)| text == "++")
+        .unwrap()
+        - 1;
+    assert_eq!(line_relative[foo_after_comment], ("foo".to_string(), 1));
+    let return_delta = line_relative
+        .iter()
+        .find(|(text, _)| text == "return")
+        .unwrap();
+    assert_eq!(return_delta.1, 4);
+}
+
+#[test]
+fn line_relative_deltas_after_continuation() {
+    let input = "#define FOO \\\n    1";
+
+    let symbols: Vec<_> = LexerBuilder::new()
+        .line_relative_deltas(true)
+        .build(input)
+        .collect();
+    assert_eq!(symbols[0].delta, 0);
+    assert_eq!(symbols[3].text(), "1");
+    assert_eq!(symbols[3].delta, 4);
+}
+
+#[test]
+fn line_relative_deltas_multiline_tokens() {
+    let input =
+        "int a; /* foo\r\n   bar */  b;\r\n\t\"baz\\\n  qux\" c; // d\n  #define E \\\r\n    1";
+
+    let mut prev_end = None;
+    for symbol in LexerBuilder::new().line_relative_deltas(true).build(input) {
+        let start = u32::from(symbol.range.start());
+        let line_start = input[..start as usize]
+            .rfind('\n')
+            .map_or(0, |idx| idx as u32 + 1);
+        let expected = match prev_end {
+            Some(end) if end > line_start => start as i32 - end as i32,
+            _ => (start - line_start) as i32,
+        };
+        assert_eq!(symbol.delta, expected, "{:?}", symbol);
+        prev_end = Some(u32::from(symbol.range.end()));
+    }
+}
+
+#[test]
+fn allow_shebang() {
+    let input = "#!/usr/bin/spcomp\nint x;";
+
+    let symbols: Vec<_> = LexerBuilder::new()
+        .allow_shebang(true)
+        .build(input)
+        .collect();
+    assert_eq!(symbols[0].token_kind, TokenKind::Shebang);
+    assert_eq!(symbols[0].text(), "#!/usr/bin/spcomp");
+    assert_eq!(symbols[0].range, TextRange::new(0.into(), 17.into()));
+    assert_eq!(symbols[1].token_kind, TokenKind::Newline);
+    assert_eq!(symbols[2].token_kind, TokenKind::Int);
+    assert_eq!(symbols[2].range, TextRange::new(18.into(), 21.into()));
+}
+
+#[test]
+fn allow_shebang_carriage_return() {
+    let input = "#!/usr/bin/spcomp\r\nint x;";
+
+    let symbols: Vec<_> = LexerBuilder::new()
+        .allow_shebang(true)
+        .build(input)
+        .collect();
+    assert_eq!(symbols[0].text(), "#!/usr/bin/spcomp");
+    assert_eq!(symbols[1].token_kind, TokenKind::Newline);
+}
+
+#[test]
+fn allow_shebang_only_first_line() {
+    let input = "int x;\n#!foo";
+
+    let symbols: Vec<_> = LexerBuilder::new()
+        .allow_shebang(true)
+        .build(input)
+        .collect();
+    assert!(symbols
+        .iter()
+        .all(|symbol| symbol.token_kind != TokenKind::Shebang));
+}
+
+#[test]
+fn shebang_disabled_by_default() {
+    let input = "#!/usr/bin/spcomp\nint x;";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+    assert_ne!(symbols[0].token_kind, TokenKind::Shebang);
+}
+
+#[test]
+fn emit_whitespace_and_legacy_dialect() {
+    let input = "new Handle:this = null;";
+
+    let symbols: Vec<_> = LexerBuilder::new()
+        .emit_whitespace(true)
+        .dialect(Dialect::Legacy)
+        .build(input)
+        .collect();
+    let kinds: Vec<_> = symbols.iter().map(|symbol| symbol.token_kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::New,
+            TokenKind::Whitespace,
+            TokenKind::Identifier,
+            TokenKind::Colon,
+            TokenKind::Identifier,
+            TokenKind::Whitespace,
+            TokenKind::Operator(Operator::Assign),
+            TokenKind::Whitespace,
+            TokenKind::Identifier,
+            TokenKind::Semicolon,
+            TokenKind::Eof
+        ]
+    );
+    assert_eq!(symbols[1].text(), " ");
+    assert_eq!(symbols[4].as_identifier(), Some("this"));
+    assert_eq!(symbols[8].as_identifier(), Some("null"));
+    assert!(symbols.iter().all(|symbol| symbol.delta == 0));
+}
+
+#[test]
+fn default_configuration() {
+    let input = "methodmap Foo { }\t\n";
+
+    let built: Vec<_> = LexerBuilder::new().build(input).collect();
+    let new: Vec<_> = SourcepawnLexer::new(input).collect();
+    assert_eq!(built, new);
+    assert_eq!(new[0].token_kind, TokenKind::Methodmap);
+    assert!(new
+        .iter()
+        .all(|symbol| symbol.token_kind != TokenKind::Whitespace));
+}
+
+#[test]
+fn emit_whitespace_reconstructs_input() {
+    let input = "int\tfoo = 1; // bar\n  foo++;";
+
+    let text: String = LexerBuilder::new()
+        .emit_whitespace(true)
+        .build(input)
+        .without_eof()
+        .map(|symbol| symbol.text().to_string())
+        .collect();
+    assert_eq!(text, input);
+}
+
+#[test]
+fn normalize_newlines_crlf() {
+    let input = "int foo;\r\nint bar;\n";
+
+    let newlines = |normalize_newlines| {
+        LexerBuilder::new()
+            .normalize_newlines(normalize_newlines)
+            .build(input)
+            .filter(|symbol| symbol.token_kind == TokenKind::Newline)
+            .map(|symbol| (symbol.text().to_string(), symbol.range))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        newlines(true),
+        vec![
+            ("\n".to_string(), TextRange::new(8.into(), 10.into())),
+            ("\n".to_string(), TextRange::new(18.into(), 19.into()))
+        ]
+    );
+    assert_eq!(
+        newlines(false),
+        vec![
+            ("\r\n".to_string(), TextRange::new(8.into(), 10.into())),
+            ("\n".to_string(), TextRange::new(18.into(), 19.into()))
+        ]
+    );
+}
+
+#[test]
+fn normalize_newlines_default() {
+    let input = "int foo;\r\n";
+
+    let built: Vec<_> = LexerBuilder::new()
+        .normalize_newlines(true)
+        .build(input)
+        .collect();
+    let new: Vec<_> = SourcepawnLexer::new(input).collect();
+    assert_eq!(built, new);
+    assert_eq!(new[3].text_ref(), None);
+}
+
+#[test]
+fn normalize_newlines_line_continuation_crlf() {
+    let input = "#define FOO \\\r\n1 \\\n+ 2\r\n";
+
+    let continuations = |normalize_newlines| {
+        LexerBuilder::new()
+            .normalize_newlines(normalize_newlines)
+            .build(input)
+            .filter(|symbol| symbol.token_kind == TokenKind::LineContinuation)
+            .map(|symbol| symbol.text().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(continuations(true), vec!["\\\n", "\\\n"]);
+    assert_eq!(continuations(false), vec!["\\\r\n", "\\\n"]);
+
+    let text: String = LexerBuilder::new()
+        .normalize_newlines(false)
+        .emit_whitespace(true)
+        .build(input)
+        .without_eof()
+        .map(|symbol| symbol.text().to_string())
+        .collect();
+    assert_eq!(text, input);
+}
+
+fn kinds_and_texts(lexer: SourcepawnLexer) -> Vec<(TokenKind, String)> {
+    lexer
+        .without_eof()
+        .map(|symbol| (symbol.token_kind, symbol.text().to_string()))
+        .collect()
+}
+
+#[test]
+fn attribute_syntax() {
+    let input = "[[foo]] int x;";
+
+    assert_eq!(
+        kinds_and_texts(LexerBuilder::new().attribute_syntax(true).build(input)),
+        vec![
+            (TokenKind::Attribute, "[[foo]]".to_string()),
+            (TokenKind::Int, "int".to_string()),
+            (TokenKind::Identifier, "x".to_string()),
+            (TokenKind::Semicolon, ";".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn attribute_syntax_disabled_by_default() {
+    let input = "[[foo]] int x;";
+
+    let kinds: Vec<_> = kinds_and_texts(SourcepawnLexer::new(input))
+        .into_iter()
+        .map(|(kind, _)| kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::LBracket,
+            TokenKind::LBracket,
+            TokenKind::Identifier,
+            TokenKind::RBracket,
+            TokenKind::RBracket,
+            TokenKind::Int,
+            TokenKind::Identifier,
+            TokenKind::Semicolon,
+        ]
+    );
+}
+
+#[test]
+fn attribute_syntax_unclosed() {
+    let input = "int x[[\n]];";
+
+    let expected = kinds_and_texts(SourcepawnLexer::new(input));
+    assert_eq!(
+        kinds_and_texts(LexerBuilder::new().attribute_syntax(true).build(input)),
+        expected
+    );
+}
+
+#[test]
+fn attribute_syntax_single_bracket() {
+    let input = "x[y[0]]";
+
+    let expected = kinds_and_texts(SourcepawnLexer::new(input));
+    assert_eq!(
+        kinds_and_texts(LexerBuilder::new().attribute_syntax(true).build(input)),
+        expected
+    );
+}
@@ -0,0 +1,127 @@
+use sourcepawn_lexer::*;
+
+#[test]
+fn from_bytes_valid() {
+    let input = "int foo = 1;\n";
+
+    let from_bytes: Vec<_> = SourcepawnLexer::from_bytes(input.as_bytes())
+        .unwrap()
+        .collect();
+    let from_str: Vec<_> = SourcepawnLexer::new(input).collect();
+    assert_eq!(from_bytes, from_str);
+}
+
+#[test]
+fn from_bytes_invalid_byte() {
+    let input = b"int foo = \xFF1;\nbar";
+
+    let symbols: Vec<_> = SourcepawnLexer::from_bytes(input).unwrap().collect();
+    let kinds: Vec<_> = symbols.iter().map(|symbol| symbol.token_kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Int,
+            TokenKind::Identifier,
+            TokenKind::Operator(Operator::Assign),
+            TokenKind::Error(LexError::InvalidUtf8),
+            TokenKind::Literal(Literal::IntegerLiteral),
+            TokenKind::Semicolon,
+            TokenKind::Newline,
+            TokenKind::Identifier,
+            TokenKind::Eof,
+        ]
+    );
+    assert_eq!(symbols[3].range, TextRange::new(10.into(), 11.into()));
+    assert_eq!(symbols[3].text(), "\u{FFFD}");
+    assert_eq!(symbols[3].delta, 1);
+    assert_eq!(symbols[4].range, TextRange::new(11.into(), 12.into()));
+    assert_eq!(symbols[4].delta, 0);
+    assert_eq!(symbols[7].text(), "bar");
+    assert_eq!(symbols[7].range, TextRange::new(14.into(), 17.into()));
+    assert_eq!(symbols[8].range, TextRange::empty(17.into()));
+}
+
+#[test]
+fn from_bytes_invalid_byte_in_define() {
+    let input = b"#define X \xFF %1 \"\xFF\"\nint";
+
+    let symbols: Vec<_> = SourcepawnLexer::from_bytes(input).unwrap().collect();
+    let newline = symbols
+        .iter()
+        .position(|symbol| symbol.token_kind == TokenKind::Newline)
+        .unwrap();
+    assert!(symbols[..newline]
+        .iter()
+        .all(|symbol| symbol.in_preprocessor));
+    assert!(symbols[newline + 1..]
+        .iter()
+        .all(|symbol| !symbol.in_preprocessor));
+    assert_eq!(symbols[3].token_kind, TokenKind::MacroParam);
+    assert_eq!(symbols[newline + 1].token_kind, TokenKind::Int);
+}
+
+#[test]
+fn from_bytes_invalid_sequence() {
+    // Truncated 3 bytes sequence, followed by a valid multibyte char.
+    let input = b"a \xE2\x82 \xC3\xA9";
+
+    let symbols: Vec<_> = SourcepawnLexer::from_bytes(input).unwrap().collect();
+    assert_eq!(
+        symbols[1].token_kind,
+        TokenKind::Error(LexError::InvalidUtf8)
+    );
+    assert_eq!(symbols[1].range, TextRange::new(2.into(), 4.into()));
+    assert_eq!(symbols.last().unwrap().range, TextRange::empty(7.into()));
+}
+
+#[test]
+fn from_bytes_invalid_at_end() {
+    let input = b"foo\xF0\x9F";
+
+    let symbols: Vec<_> = SourcepawnLexer::from_bytes(input).unwrap().collect();
+    assert_eq!(symbols.len(), 3);
+    assert_eq!(
+        symbols[1].token_kind,
+        TokenKind::Error(LexError::InvalidUtf8)
+    );
+    assert_eq!(symbols[1].range, TextRange::new(3.into(), 5.into()));
+    assert_eq!(symbols[2].token_kind, TokenKind::Eof);
+}
+
+#[test]
+fn from_bytes_empty() {
+    let symbols: Vec<_> = SourcepawnLexer::from_bytes(b"").unwrap().collect();
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].token_kind, TokenKind::Eof);
+}
+
+#[test]
+fn from_bytes_utf8_bom() {
+    let input = b"\xEF\xBB\xBFint foo;";
+
+    let symbols: Vec<_> = SourcepawnLexer::from_bytes(input).unwrap().collect();
+    assert_eq!(symbols[0].token_kind, TokenKind::Int);
+    assert_eq!(symbols[0].range, TextRange::new(3.into(), 6.into()));
+    assert_eq!(symbols[0].delta, 0);
+    assert_eq!(symbols.last().unwrap().range, TextRange::empty(11.into()));
+}
+
+#[test]
+fn from_bytes_utf16_le_bom() {
+    let input = b"\xFF\xFEi\0n\0t\0";
+
+    assert_eq!(
+        SourcepawnLexer::from_bytes(input).err(),
+        Some(LexError::UnsupportedEncoding)
+    );
+}
+
+#[test]
+fn from_bytes_utf16_be_bom() {
+    let input = b"\xFE\xFF\0i\0n\0t";
+
+    assert_eq!(
+        SourcepawnLexer::from_bytes(input).err(),
+        Some(LexError::UnsupportedEncoding)
+    );
+}
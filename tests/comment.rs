@@ -0,0 +1,84 @@
+use sourcepawn_lexer::{Comment, SourcepawnLexer, TextRange, TokenKind};
+
+#[test]
+fn line_comment_trailing_backslash() {
+    let input = "// foo \\\nbar";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).without_eof().collect();
+    let kinds: Vec<_> = symbols.iter().map(|symbol| symbol.token_kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Comment(Comment::LineComment),
+            TokenKind::Newline,
+            TokenKind::Identifier
+        ]
+    );
+    assert_eq!(symbols[0].text(), "// foo \\");
+    assert_eq!(symbols[2].text(), "bar");
+}
+
+#[test]
+fn line_comment_trailing_backslash_crlf() {
+    let input = "// foo \\\r\nbar";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).without_eof().collect();
+    assert_eq!(symbols[0].text(), "// foo \\");
+    assert_eq!(symbols[1].token_kind, TokenKind::Newline);
+    assert_eq!(symbols[2].token_kind, TokenKind::Identifier);
+}
+
+#[test]
+fn line_comment_trailing_backslash_in_define() {
+    let input = "#define FOO 1 // foo \\\nbar";
+
+    let mut lexer = SourcepawnLexer::new(input);
+    let symbol = lexer
+        .find(|symbol| symbol.token_kind == TokenKind::Identifier && symbol.text() == "bar")
+        .unwrap();
+    assert_eq!(symbol.range, TextRange::new(23.into(), 26.into()));
+    assert!(!symbol.in_preprocessor);
+}
+
+#[test]
+fn unterminated_block_comment() {
+    let input = "int x; /* never\nclosed";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+    assert_eq!(symbols.len(), 5);
+    assert_eq!(
+        symbols[3].token_kind,
+        TokenKind::Comment(Comment::UnterminatedBlockComment)
+    );
+    assert_eq!(symbols[3].text(), "/* never\nclosed");
+    assert_eq!(symbols[3].range, TextRange::new(7.into(), 22.into()));
+    assert_eq!(symbols[4].token_kind, TokenKind::Eof);
+}
+
+#[test]
+fn terminated_block_comment() {
+    let input = "/* closed */ int x;";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+    assert_eq!(
+        symbols[0].token_kind,
+        TokenKind::Comment(Comment::BlockComment)
+    );
+    assert!(symbols
+        .iter()
+        .all(|symbol| symbol.token_kind != TokenKind::Comment(Comment::UnterminatedBlockComment)));
+}
+
+#[test]
+fn unterminated_block_comment_ends_preprocessor() {
+    let input = "#define FOO 1 /* never\nclosed";
+
+    let mut lexer = SourcepawnLexer::new(input);
+    let symbol = lexer.nth(3).unwrap();
+    assert_eq!(
+        symbol.token_kind,
+        TokenKind::Comment(Comment::UnterminatedBlockComment)
+    );
+    assert_eq!(lexer.next().unwrap().token_kind, TokenKind::Eof);
+    assert!(!lexer.in_preprocessor());
+}
@@ -0,0 +1,33 @@
+mod utils;
+
+use insta::assert_json_snapshot;
+use sourcepawn_lexer::*;
+use utils::collect_tokens;
+
+#[test]
+fn native_default_args_and_varargs() {
+    let input = r#"native int Foo(int x = 5, ...);
+"#;
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_json_snapshot!(collect_tokens(&mut lexer));
+}
+
+#[test]
+fn native_old_syntax() {
+    let input = r#"native Float:Bar(const String:name[], Float:value=-1.0, any:...);
+"#;
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_json_snapshot!(collect_tokens(&mut lexer));
+}
+
+#[test]
+fn forward_declarations() {
+    let input = r#"forward void OnFoo(int client, bool &handled = false);
+forward Action OnBar(const char[] name, int flags=0x1F, any ...);
+"#;
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_json_snapshot!(collect_tokens(&mut lexer));
+}
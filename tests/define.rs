@@ -2,7 +2,7 @@ mod utils;
 
 use insta::assert_json_snapshot;
 use sourcepawn_lexer::*;
-use utils::collect_tokens;
+use utils::{collect_tokens, kinds};
 
 #[test]
 fn define_simple() {
@@ -104,3 +104,182 @@ fn define_line_continuation_carriage_return() {
     let mut lexer = SourcepawnLexer::new(input);
     assert_json_snapshot!(collect_tokens(&mut lexer));
 }
+
+#[test]
+fn define_in_preprocessor_at_eof() {
+    let mut lexer = SourcepawnLexer::new("#define FOO");
+
+    let mut in_preprocessor = Vec::new();
+    while let Some(symbol) = lexer.next() {
+        in_preprocessor.push((symbol.token_kind, lexer.in_preprocessor()));
+    }
+    assert_eq!(
+        in_preprocessor,
+        vec![
+            (TokenKind::PreprocDir(PreprocDir::MDefine), true),
+            (TokenKind::Identifier, true),
+            (TokenKind::Eof, false)
+        ]
+    );
+    assert!(!lexer.in_preprocessor());
+}
+
+#[test]
+fn endinput_in_preprocessor_at_eof() {
+    let mut lexer = SourcepawnLexer::new("int foo;\n#endinput");
+
+    let symbols: Vec<_> = lexer
+        .by_ref()
+        .take_while(|symbol| symbol.token_kind != TokenKind::Eof)
+        .collect();
+    assert_eq!(
+        symbols.last().unwrap().token_kind,
+        TokenKind::PreprocDir(PreprocDir::MEndinput)
+    );
+    assert!(!lexer.in_preprocessor());
+}
+
+#[test]
+fn define_line_continuation_at_eof() {
+    let mut lexer = SourcepawnLexer::new("#define FOO \\\n");
+
+    assert_eq!(lexer.by_ref().last().unwrap().token_kind, TokenKind::Eof);
+    assert!(!lexer.in_preprocessor());
+}
+
+#[test]
+fn define_redefined() {
+    let input = r#"#define FOO 1
+#define FOO 2
+"#;
+
+    let mut lexer = LexerBuilder::new().track_defines(true).build(input);
+    lexer.by_ref().count();
+    assert_eq!(
+        lexer.diagnostics(),
+        &[(
+            TextRange::new(22.into(), 25.into()),
+            LexError::RedefinedMacro
+        )]
+    );
+    assert_eq!(lexer.defines().get("FOO").unwrap(), "2");
+}
+
+#[test]
+fn define_identical_redefinition() {
+    let input = r#"#define FOO 1 + /* bar */ 2
+#define FOO 1 +  2 // baz
+"#;
+
+    let mut lexer = LexerBuilder::new().track_defines(true).build(input);
+    lexer.by_ref().count();
+    assert!(lexer.diagnostics().is_empty());
+    assert_eq!(lexer.defines().get("FOO").unwrap(), "1 + 2");
+}
+
+#[test]
+fn define_undef() {
+    let input = r#"#define FOO 1
+#undef FOO
+#define FOO 2
+#define BAR"#;
+
+    let mut lexer = LexerBuilder::new().track_defines(true).build(input);
+    lexer.by_ref().count();
+    assert!(lexer.diagnostics().is_empty());
+    assert_eq!(lexer.defines().get("FOO").unwrap(), "2");
+    assert_eq!(lexer.defines().get("BAR").unwrap(), "");
+}
+
+#[test]
+fn define_not_tracked() {
+    let mut lexer = SourcepawnLexer::new("#define FOO 1\n#define FOO 2\n");
+    lexer.by_ref().count();
+    assert!(lexer.diagnostics().is_empty());
+    assert!(lexer.defines().is_empty());
+}
+
+#[test]
+fn define_macro_params() {
+    let input = "#define M(%1,%2) %1 % %2";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).without_eof().collect();
+    let params: Vec<_> = symbols
+        .iter()
+        .filter(|symbol| symbol.token_kind == TokenKind::MacroParam)
+        .map(|symbol| symbol.text())
+        .collect();
+    assert_eq!(params, vec!["%1", "%2", "%1", "%2"]);
+    assert_eq!(
+        kinds(input)[7..],
+        [
+            TokenKind::MacroParam,
+            TokenKind::Operator(Operator::Percent),
+            TokenKind::MacroParam
+        ]
+    );
+}
+
+#[test]
+fn define_macro_param_single_digit() {
+    assert_eq!(
+        kinds("#define M(%1) %10"),
+        vec![
+            TokenKind::PreprocDir(PreprocDir::MDefine),
+            TokenKind::Identifier,
+            TokenKind::LParen,
+            TokenKind::MacroParam,
+            TokenKind::RParen,
+            TokenKind::MacroParam,
+            TokenKind::Literal(Literal::IntegerLiteral)
+        ]
+    );
+}
+
+#[test]
+fn define_macro_param_continuation() {
+    let input = "#define M(%1) \\\n    %1\nx = %1;";
+
+    let kinds = kinds(input);
+    assert_eq!(kinds[6], TokenKind::MacroParam);
+    assert_eq!(
+        kinds[8..],
+        [
+            TokenKind::Identifier,
+            TokenKind::Operator(Operator::Assign),
+            TokenKind::Operator(Operator::Percent),
+            TokenKind::Literal(Literal::IntegerLiteral),
+            TokenKind::Semicolon
+        ]
+    );
+}
+
+#[test]
+fn modulo_outside_define() {
+    assert_eq!(
+        kinds("x = a % b;\nx = a %2;"),
+        vec![
+            TokenKind::Identifier,
+            TokenKind::Operator(Operator::Assign),
+            TokenKind::Identifier,
+            TokenKind::Operator(Operator::Percent),
+            TokenKind::Identifier,
+            TokenKind::Semicolon,
+            TokenKind::Newline,
+            TokenKind::Identifier,
+            TokenKind::Operator(Operator::Assign),
+            TokenKind::Identifier,
+            TokenKind::Operator(Operator::Percent),
+            TokenKind::Literal(Literal::IntegerLiteral),
+            TokenKind::Semicolon
+        ]
+    );
+}
+
+#[test]
+fn percent_in_other_directive() {
+    assert_eq!(
+        kinds("#if FOO %2\n#endif")[2],
+        TokenKind::Operator(Operator::Percent)
+    );
+}
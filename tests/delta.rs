@@ -0,0 +1,20 @@
+use sourcepawn_lexer::*;
+
+#[test]
+fn delta_cursor_reconstructs_ranges() {
+    let input = r#"  int foo = 1; // foo
+/* bar
+ */ #define FOO \
+    "baz"
+"#;
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+    let mut cursor = DeltaCursor::new(symbols[0].range.start());
+    let ranges: Vec<_> = symbols
+        .iter()
+        .map(|symbol| cursor.next_range(symbol.delta, symbol.range.len()))
+        .collect();
+    let expected: Vec<_> = symbols.iter().map(|symbol| symbol.range).collect();
+    assert_eq!(ranges, expected);
+    assert_eq!(ranges[0].start(), 2.into());
+}
@@ -0,0 +1,57 @@
+mod utils;
+
+use insta::assert_json_snapshot;
+use sourcepawn_lexer::*;
+use utils::collect_tokens;
+
+#[test]
+fn error_simple() {
+    let input = r#"#error This plugin can't be compiled
+int foo;
+"#;
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_json_snapshot!(collect_tokens(&mut lexer));
+}
+
+#[test]
+fn warning_simple() {
+    let input = r#"#warning "Deprecated include"
+int foo;
+"#;
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_json_snapshot!(collect_tokens(&mut lexer));
+}
+
+#[test]
+fn error_in_preprocessor() {
+    let mut lexer = SourcepawnLexer::new("#error foo // bar\nint foo;");
+
+    let symbol = lexer.next().unwrap();
+    assert_eq!(symbol.token_kind, TokenKind::PreprocDir(PreprocDir::MError));
+    assert!(lexer.in_preprocessor());
+    assert_eq!(symbol.directive_argument(), Some("foo".to_string()));
+    assert_eq!(
+        lexer.next().unwrap().token_kind,
+        TokenKind::Comment(Comment::LineComment)
+    );
+    assert!(lexer.in_preprocessor());
+    assert_eq!(lexer.next().unwrap().token_kind, TokenKind::Newline);
+    assert!(!lexer.in_preprocessor());
+}
+
+#[test]
+fn warning_line_continuation() {
+    let mut lexer = SourcepawnLexer::new("#warning foo \\\nbar\nint foo;");
+
+    let symbol = lexer.next().unwrap();
+    assert_eq!(
+        symbol.token_kind,
+        TokenKind::PreprocDir(PreprocDir::MWarning)
+    );
+    assert!(lexer.in_preprocessor());
+    assert_eq!(symbol.directive_argument(), Some("foo bar".to_string()));
+    assert_eq!(lexer.next().unwrap().token_kind, TokenKind::Newline);
+    assert_eq!(lexer.next().unwrap().token_kind, TokenKind::Int);
+}
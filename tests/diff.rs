@@ -0,0 +1,119 @@
+use sourcepawn_lexer::*;
+
+fn lex(input: &str) -> Vec<Symbol> {
+    SourcepawnLexer::new(input).collect()
+}
+
+#[test]
+fn token_diff_edit_in_middle() {
+    let old = lex("int foo;\nint bar = 1;\nint baz;\n");
+    let new = lex("int foo;\nint bar = 1 + 2;\nint baz;\n");
+
+    let diff = token_diff(&old, &new);
+    assert_eq!(diff.unchanged_prefix, 8);
+    assert_eq!(diff.unchanged_suffix, 7);
+    assert_eq!(diff.changed, 8..10);
+    let changed: Vec<_> = new[diff.changed].iter().map(|s| s.text()).collect();
+    assert_eq!(changed, vec!["+", "2"]);
+    assert!(old[diff.unchanged_prefix..old.len() - diff.unchanged_suffix].is_empty());
+}
+
+#[test]
+fn token_diff_replacement() {
+    let old = lex("foo(1);\nbar();");
+    let new = lex("foo(2);\nbar();");
+
+    let diff = token_diff(&old, &new);
+    assert_eq!(diff.unchanged_prefix, 2);
+    assert_eq!(diff.changed, 2..3);
+    assert_eq!(diff.unchanged_suffix, old.len() - 3);
+    assert_eq!(new[2].text(), "2");
+}
+
+#[test]
+fn token_diff_identical() {
+    let old = lex("int foo;");
+    let new = lex("int  foo;");
+
+    let diff = token_diff(&old, &new);
+    assert_eq!(diff.unchanged_prefix, old.len());
+    assert_eq!(diff.changed, new.len()..new.len());
+    assert_eq!(diff.unchanged_suffix, 0);
+}
+
+#[test]
+fn token_diff_repeated_tokens() {
+    // The prefix and suffix must not overlap when the inserted tokens repeat their neighbours.
+    let old = lex("a;");
+    let new = lex("a;a;");
+
+    let diff = token_diff(&old, &new);
+    assert_eq!(diff.unchanged_prefix, 2);
+    assert_eq!(diff.unchanged_suffix, 1);
+    assert_eq!(diff.changed, 2..4);
+}
+
+#[test]
+fn eq_ignoring_position() {
+    let old = lex("int foo;");
+    let new = lex("\n\nint foo;");
+
+    assert!(old[1].eq_ignoring_position(&new[3]));
+    assert_ne!(old[1], new[3]);
+    assert!(!old[0].eq_ignoring_position(&old[1]));
+}
+
+/// Inserts a synthetic `;` at the end of the symbol at `idx`.
+fn insert_semicolon(symbols: &mut Vec<Symbol>, idx: usize) {
+    let range = TextRange::empty(symbols[idx].range.end());
+    symbols.insert(idx + 1, Symbol::new(TokenKind::Semicolon, None, range, 0));
+}
+
+#[test]
+fn reconstruct_skips_synthetic_symbols() {
+    let input = "int foo = 1\nint bar;";
+    let mut symbols: Vec<_> = LexerBuilder::new()
+        .emit_whitespace(true)
+        .build(input)
+        .collect();
+    insert_semicolon(&mut symbols, 6);
+
+    assert!(symbols[7].is_synthetic());
+    assert_eq!(symbols[7].text(), ";");
+    assert_eq!(reconstruct(&symbols), input);
+}
+
+#[test]
+fn reconstruct_fills_gaps() {
+    let input = "int\tfoo = 1; // bar\r\n  foo++;";
+    let symbols = lex(input);
+
+    assert_eq!(reconstruct(&symbols), "int foo = 1; // bar\n  foo++;");
+    let symbols: Vec<_> = LexerBuilder::new()
+        .emit_whitespace(true)
+        .normalize_newlines(false)
+        .build(input)
+        .collect();
+    assert_eq!(reconstruct(&symbols), input);
+}
+
+#[test]
+fn token_diff_synthetic_symbol() {
+    // The synthetic `;` is not matched with the lexed `;` which follows it.
+    let old = lex("foo();\n;");
+    let mut new = old.clone();
+    insert_semicolon(&mut new, 3);
+
+    let diff = token_diff(&old, &new);
+    assert_eq!(diff.unchanged_prefix, 4);
+    assert_eq!(diff.changed, 4..5);
+    assert_eq!(diff.unchanged_suffix, old.len() - 4);
+    assert!(new[diff.changed][0].is_synthetic());
+}
+
+#[test]
+fn is_synthetic_eof() {
+    let symbols = lex("");
+    assert_eq!(symbols[0].token_kind, TokenKind::Eof);
+    assert!(!symbols[0].is_synthetic());
+}
@@ -0,0 +1,57 @@
+use sourcepawn_lexer::*;
+
+#[test]
+fn display_static_text() {
+    assert_eq!(TokenKind::Operator(Operator::Plus).to_string(), "+");
+    assert_eq!(TokenKind::Operator(Operator::Ushr).to_string(), ">>>");
+    assert_eq!(TokenKind::Semicolon.to_string(), ";");
+    assert_eq!(TokenKind::Int.to_string(), "int");
+    assert_eq!(TokenKind::ViewAs.to_string(), "view_as");
+    assert_eq!(
+        TokenKind::PreprocDir(PreprocDir::MDefine).to_string(),
+        "#define"
+    );
+}
+
+#[test]
+fn display_label() {
+    assert_eq!(TokenKind::Identifier.to_string(), "identifier");
+    assert_eq!(
+        TokenKind::Literal(Literal::StringLiteral).to_string(),
+        "string literal"
+    );
+    assert_eq!(
+        TokenKind::Comment(Comment::BlockComment).to_string(),
+        "block comment"
+    );
+    assert_eq!(
+        TokenKind::PreprocDir(PreprocDir::MPragma).to_string(),
+        "#pragma"
+    );
+    assert_eq!(
+        TokenKind::Error(LexError::UnterminatedString).to_string(),
+        "unterminated string"
+    );
+    assert_eq!(TokenKind::Newline.to_string(), "newline");
+    assert_eq!(TokenKind::Unknown.to_string(), "unknown token");
+    assert_eq!(TokenKind::Eof.to_string(), "end of file");
+}
+
+#[test]
+fn display_error_message() {
+    let symbol = SourcepawnLexer::new("foo").next().unwrap();
+    let message = format!(
+        "expected `{}`, found {}",
+        TokenKind::Semicolon,
+        symbol.token_kind
+    );
+    assert_eq!(message, "expected `;`, found identifier");
+}
+
+#[test]
+fn display_payloads() {
+    assert_eq!(Operator::AssignAdd.to_string(), "+=");
+    assert_eq!(PreprocDir::MInclude.to_string(), "#include");
+    assert_eq!(Literal::HexLiteral.to_string(), "hexadecimal literal");
+    assert_eq!(Comment::LineComment.to_string(), "line comment");
+}
@@ -0,0 +1,55 @@
+mod utils;
+
+use insta::assert_json_snapshot;
+use sourcepawn_lexer::*;
+use utils::collect_tokens;
+
+#[test]
+fn file_simple() {
+    let input = r#"#file "plugin.sp"
+int foo;
+"#;
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_json_snapshot!(collect_tokens(&mut lexer));
+}
+
+#[test]
+fn file_directive_path_quoted() {
+    let symbol = SourcepawnLexer::new(r#"#file "plugin.sp""#).next().unwrap();
+    assert_eq!(symbol.file_directive_path(), Some("plugin.sp".to_string()));
+}
+
+#[test]
+fn file_directive_path_quoted_with_spaces() {
+    let input = "#file \"C:\\my plugins\\plugin.sp\" // comment\n";
+
+    let symbol = SourcepawnLexer::new(input).next().unwrap();
+    assert_eq!(
+        symbol.file_directive_path(),
+        Some("C:\\my plugins\\plugin.sp".to_string())
+    );
+}
+
+#[test]
+fn file_directive_path_bare() {
+    let symbol = SourcepawnLexer::new("#file include/sourcemod.inc\n")
+        .next()
+        .unwrap();
+    assert_eq!(
+        symbol.file_directive_path(),
+        Some("include/sourcemod.inc".to_string())
+    );
+}
+
+#[test]
+fn file_directive_path_empty() {
+    let symbol = SourcepawnLexer::new("#file\n").next().unwrap();
+    assert_eq!(symbol.file_directive_path(), None);
+}
+
+#[test]
+fn file_directive_path_not_file() {
+    let symbol = SourcepawnLexer::new(r#""plugin.sp""#).next().unwrap();
+    assert_eq!(symbol.file_directive_path(), None);
+}
@@ -0,0 +1,50 @@
+use sourcepawn_lexer::{brace_fold_ranges, SourcepawnLexer, Symbol};
+
+fn fold_ranges(input: &str) -> Vec<&str> {
+    let symbols: Vec<Symbol> = SourcepawnLexer::new(input).collect();
+    brace_fold_ranges(&symbols)
+        .into_iter()
+        .map(|range| &input[range])
+        .collect()
+}
+
+#[test]
+fn brace_fold_ranges_nested() {
+    let input = r#"enum struct Foo {
+    void Bar() {
+        if (true) {
+        }
+    }
+    void Baz() {}
+}
+"#;
+
+    assert_eq!(
+        fold_ranges(input),
+        vec![
+            "{\n    void Bar() {\n        if (true) {\n        }\n    }\n    void Baz() {}\n}",
+            "{\n        if (true) {\n        }\n    }",
+            "{\n        }",
+            "{}"
+        ]
+    );
+}
+
+#[test]
+fn brace_fold_ranges_unclosed() {
+    let input = "void foo() {\n    if (bar) {\n    }\n";
+
+    assert_eq!(fold_ranges(input), vec!["{\n    }"]);
+}
+
+#[test]
+fn brace_fold_ranges_unmatched_closer() {
+    let input = "}\nvoid foo() {\n}\n}";
+
+    assert_eq!(fold_ranges(input), vec!["{\n}"]);
+}
+
+#[test]
+fn brace_fold_ranges_empty() {
+    assert!(brace_fold_ranges(&[]).is_empty());
+}
@@ -29,3 +29,103 @@ fn include_line_continuation_1() {
     let mut lexer = SourcepawnLexer::new(input);
     assert_json_snapshot!(collect_tokens(&mut lexer));
 }
+
+fn include_path(input: &str) -> Option<String> {
+    SourcepawnLexer::new(input).next().unwrap().include_path()
+}
+
+#[test]
+fn include_path_unix_separator() {
+    assert_eq!(
+        include_path(r#"#include "a/b.inc""#),
+        Some("a/b.inc".to_string())
+    );
+}
+
+#[test]
+fn include_path_windows_separator() {
+    assert_eq!(
+        include_path(r#"#include "a\b.inc""#),
+        Some(r"a\b.inc".to_string())
+    );
+    // Escape sequences are not processed.
+    assert_eq!(
+        include_path(r#"#include "a\\b\n.inc""#),
+        Some(r"a\\b\n.inc".to_string())
+    );
+}
+
+#[test]
+fn include_path_chevrons() {
+    assert_eq!(
+        include_path("#include <sourcemod>\n"),
+        Some("sourcemod".to_string())
+    );
+    assert_eq!(
+        include_path("#tryinclude <a\\b> // comment"),
+        Some(r"a\b".to_string())
+    );
+}
+
+#[test]
+fn include_path_none() {
+    assert_eq!(include_path("#include"), None);
+    assert_eq!(include_path("#include <>"), None);
+    assert_eq!(include_path("#pragma semicolon 1"), None);
+}
+
+fn include(input: &str) -> Option<(String, IncludeKind)> {
+    let include = SourcepawnLexer::new(input).next().unwrap().as_include()?;
+    Some((include.path.to_string(), include.kind))
+}
+
+#[test]
+fn as_include_chevrons() {
+    assert_eq!(
+        include("#include <sourcemod>\n"),
+        Some(("sourcemod".to_string(), IncludeKind::Chevron))
+    );
+    assert_eq!(
+        include("#tryinclude <sdktools>"),
+        Some(("sdktools".to_string(), IncludeKind::Chevron))
+    );
+}
+
+#[test]
+fn as_include_quotes() {
+    assert_eq!(
+        include(r#"#include "foo.sp""#),
+        Some(("foo.sp".to_string(), IncludeKind::Quote))
+    );
+    assert_eq!(
+        include(r#"#include "a\b.inc" // comment"#),
+        Some((r"a\b.inc".to_string(), IncludeKind::Quote))
+    );
+}
+
+#[test]
+fn as_include_line_continuation() {
+    assert_eq!(
+        include("#include <sourcemod\\\n>"),
+        Some(("sourcemod".to_string(), IncludeKind::Chevron))
+    );
+    assert_eq!(
+        include("#include \\\r\n\"foo.sp\""),
+        Some(("foo.sp".to_string(), IncludeKind::Quote))
+    );
+}
+
+#[test]
+fn as_include_bare() {
+    assert_eq!(
+        include("#include sourcemod"),
+        Some(("sourcemod".to_string(), IncludeKind::Bare))
+    );
+}
+
+#[test]
+fn as_include_none() {
+    assert_eq!(include("#include"), None);
+    assert_eq!(include("#include \"\""), None);
+    assert_eq!(include("#define FOO"), None);
+}
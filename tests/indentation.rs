@@ -0,0 +1,48 @@
+use sourcepawn_lexer::{detect_mixed_indentation, LexerBuilder, SourcepawnLexer, Symbol};
+
+fn mixed_indentation(input: &str) -> Vec<&str> {
+    let symbols: Vec<Symbol> = LexerBuilder::new()
+        .emit_whitespace(true)
+        .build(input)
+        .collect();
+    detect_mixed_indentation(&symbols)
+        .into_iter()
+        .map(|range| &input[range])
+        .collect()
+}
+
+#[test]
+fn mixed_indentation_tab_then_spaces() {
+    let input = "void foo() {\n\t  int bar;\n}";
+
+    assert_eq!(mixed_indentation(input), vec!["\t  "]);
+}
+
+#[test]
+fn mixed_indentation_pure() {
+    let input = "void foo() {\n    int bar;\n\t\tint baz;\n}";
+
+    assert!(mixed_indentation(input).is_empty());
+}
+
+#[test]
+fn mixed_indentation_not_leading() {
+    // Only the whitespace at the start of a line is indentation.
+    let input = "int foo;\t  // bar\n \tint baz;";
+
+    assert_eq!(mixed_indentation(input), vec![" \t"]);
+}
+
+#[test]
+fn mixed_indentation_after_line_continuation() {
+    let input = "#define FOO \\\n\t  1";
+
+    assert_eq!(mixed_indentation(input), vec!["\t  "]);
+}
+
+#[test]
+fn mixed_indentation_without_whitespace_tokens() {
+    let symbols: Vec<Symbol> = SourcepawnLexer::new("\t  int foo;").collect();
+
+    assert!(detect_mixed_indentation(&symbols).is_empty());
+}
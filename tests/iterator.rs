@@ -0,0 +1,680 @@
+use sourcepawn_lexer::{
+    Checkpoint, Comment, DeltaCursor, Dialect, LexError, LexerBuilder, Operator, Piece,
+    SourcepawnLexer, TextRange, TokenKind,
+};
+
+#[test]
+fn without_eof_simple() {
+    let input = "int foo = 1;\n";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).without_eof().collect();
+    let all: Vec<_> = SourcepawnLexer::new(input).collect();
+    assert_eq!(all.last().unwrap().token_kind, TokenKind::Eof);
+    assert_eq!(symbols.last().unwrap().token_kind, TokenKind::Newline);
+    assert_eq!(symbols[..], all[..all.len() - 1]);
+}
+
+#[test]
+fn without_eof_empty() {
+    assert_eq!(SourcepawnLexer::new("").without_eof().count(), 0);
+}
+
+#[test]
+fn without_eof_preprocessor() {
+    let input = "#define FOO 1";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).without_eof().collect();
+    assert!(symbols
+        .iter()
+        .all(|symbol| symbol.token_kind != TokenKind::Eof));
+    assert_eq!(symbols.len(), 3);
+}
+
+#[test]
+fn has_significant_tokens_empty() {
+    assert!(!SourcepawnLexer::has_significant_tokens(""));
+}
+
+#[test]
+fn has_significant_tokens_whitespace() {
+    assert!(!SourcepawnLexer::has_significant_tokens(" \t\r\n\n  \\\n"));
+}
+
+#[test]
+fn has_significant_tokens_comments() {
+    let input = r#"// foo
+/* bar
+ * baz */
+"#;
+
+    assert!(!SourcepawnLexer::has_significant_tokens(input));
+}
+
+#[test]
+fn has_significant_tokens_one_token() {
+    let input = r#"// foo
+foo
+"#;
+
+    assert!(SourcepawnLexer::has_significant_tokens(input));
+}
+
+#[test]
+fn next_past_eof() {
+    let mut lexer = SourcepawnLexer::new("int foo;");
+
+    assert_eq!(lexer.by_ref().last().unwrap().token_kind, TokenKind::Eof);
+    for _ in 0..5 {
+        assert_eq!(lexer.next(), None);
+    }
+    assert!(!lexer.in_preprocessor());
+}
+
+#[test]
+fn next_past_eof_unterminated_preprocessor() {
+    let mut lexer = SourcepawnLexer::new("#define FOO \\");
+
+    let eof_count = lexer
+        .by_ref()
+        .filter(|symbol| symbol.token_kind == TokenKind::Eof)
+        .count();
+    assert_eq!(eof_count, 1);
+    for _ in 0..5 {
+        assert_eq!(lexer.next(), None);
+    }
+}
+
+#[test]
+fn pieces_tags() {
+    let input = r#"int foo; // foo
+/* bar */ \
+foo"#;
+
+    let pieces: Vec<_> = SourcepawnLexer::new(input).pieces().collect();
+    let trivia: Vec<_> = pieces
+        .iter()
+        .filter(|piece| piece.is_trivia())
+        .map(|piece| piece.symbol().token_kind)
+        .collect();
+    assert_eq!(
+        trivia,
+        vec![
+            TokenKind::Comment(Comment::LineComment),
+            TokenKind::Newline,
+            TokenKind::Comment(Comment::BlockComment),
+            TokenKind::LineContinuation
+        ]
+    );
+    for piece in &pieces {
+        if piece.symbol().token_kind == TokenKind::Identifier {
+            assert!(matches!(piece, Piece::Token(_)));
+        }
+    }
+    assert!(
+        matches!(pieces.last(), Some(Piece::Token(symbol)) if symbol.token_kind == TokenKind::Eof)
+    );
+}
+
+#[test]
+fn pieces_into_symbols() {
+    let input = "int foo; // foo\n";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input)
+        .pieces()
+        .map(Piece::into_symbol)
+        .collect();
+    assert_eq!(symbols, SourcepawnLexer::new(input).collect::<Vec<_>>());
+}
+
+#[test]
+fn eof_empty_input() {
+    let symbols: Vec<_> = SourcepawnLexer::new("").collect();
+
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].token_kind, TokenKind::Eof);
+    assert_eq!(symbols[0].range, TextRange::empty(0.into()));
+    assert_eq!(symbols[0].delta, 0);
+}
+
+#[test]
+fn eof_whitespace_input() {
+    let symbols: Vec<_> = SourcepawnLexer::new("   ").collect();
+
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].token_kind, TokenKind::Eof);
+    assert_eq!(symbols[0].range, TextRange::empty(3.into()));
+    assert_eq!(symbols[0].delta, 0);
+}
+
+#[test]
+fn eof_trailing_whitespace() {
+    let symbols: Vec<_> = SourcepawnLexer::new("int   ").collect();
+
+    assert_eq!(symbols.len(), 2);
+    assert_eq!(symbols[1].token_kind, TokenKind::Eof);
+    assert_eq!(symbols[1].range, TextRange::empty(6.into()));
+    assert_eq!(symbols[1].delta, 3);
+}
+
+#[test]
+fn join_continuations_define() {
+    let input = "#define FOO(%1) \\\n    %1 + \\\r\n 1\nint foo;";
+
+    let raw: Vec<_> = SourcepawnLexer::new(input).collect();
+    let joined: Vec<_> = SourcepawnLexer::new(input).join_continuations().collect();
+    let continuations: Vec<_> = raw
+        .iter()
+        .filter(|symbol| symbol.token_kind == TokenKind::LineContinuation)
+        .collect();
+    assert_eq!(continuations.len(), 2);
+    assert_eq!(joined.len(), raw.len() - 2);
+    assert!(joined
+        .iter()
+        .all(|symbol| symbol.token_kind != TokenKind::LineContinuation));
+
+    // Ranges are unchanged, and deltas are relative to the previous joined token.
+    let mut prev_end = None;
+    for symbol in &joined {
+        assert!(raw.iter().any(|raw| raw.range == symbol.range));
+        if let Some(prev_end) = prev_end {
+            assert_eq!(
+                symbol.delta,
+                u32::from(symbol.range.start()) as i32 - u32::from(prev_end) as i32
+            );
+        }
+        prev_end = Some(symbol.range.end());
+    }
+}
+
+#[test]
+fn join_continuations_without_continuation() {
+    let input = "int foo;\n";
+
+    let raw: Vec<_> = SourcepawnLexer::new(input).collect();
+    let joined: Vec<_> = SourcepawnLexer::new(input).join_continuations().collect();
+    assert_eq!(raw, joined);
+}
+
+#[test]
+fn borrowed_lexer_iterates_twice() {
+    let mut lexer = SourcepawnLexer::new("int foo;\nfoo = 1;\n");
+    lexer.next();
+
+    let first: Vec<_> = (&lexer).into_iter().collect();
+    let mut second = vec![];
+    for symbol in &lexer {
+        second.push(symbol);
+    }
+    assert_eq!(first, second);
+    assert_eq!(first.len(), 9);
+    assert_eq!(first.first().unwrap().text(), "foo");
+    assert_eq!(lexer.next().unwrap().text(), "foo");
+}
+
+#[test]
+fn comments_only() {
+    let input = r#"// foo
+int foo; /* bar */
+#pragma semicolon 1 // baz
+"#;
+
+    let comments: Vec<_> = SourcepawnLexer::new(input)
+        .comments()
+        .map(|symbol| symbol.text())
+        .collect();
+    assert_eq!(comments, vec!["// foo", "/* bar */", "// baz"]);
+}
+
+#[test]
+fn doc_comments_native() {
+    let input = r#"// Not documentation.
+/// Returns the client count.
+///
+/// @return Client count.
+native int GetClientCount();
+
+/**
+ * Foo.
+ */
+stock void Foo() {}
+////////
+/** Eof. */"#;
+
+    let pairs: Vec<_> = SourcepawnLexer::new(input)
+        .doc_comments()
+        .map(|(comment, symbol)| (comment.text(), symbol.token_kind))
+        .collect();
+    assert_eq!(
+        pairs,
+        vec![
+            ("/// Returns the client count.".into(), TokenKind::Native),
+            ("///".into(), TokenKind::Native),
+            ("/// @return Client count.".into(), TokenKind::Native),
+            ("/**\n * Foo.\n */".into(), TokenKind::Stock),
+            ("/** Eof. */".into(), TokenKind::Eof),
+        ]
+    );
+}
+
+#[test]
+fn is_doc_comment() {
+    let doc: Vec<_> = SourcepawnLexer::new("/// a\n/** b */\n// c\n//// d\n/* e */\n/***/\n/**/")
+        .comments()
+        .map(|symbol| symbol.is_doc_comment())
+        .collect();
+    assert_eq!(doc, vec![true, true, false, false, false, false, false]);
+}
+
+#[test]
+fn last_symbol_matches_next() {
+    let mut lexer = SourcepawnLexer::new("#!foo\nint foo; // bar\n");
+    assert!(lexer.last_symbol().is_none());
+    while let Some(symbol) = lexer.next() {
+        assert_eq!(lexer.last_symbol(), Some(symbol));
+    }
+    assert_eq!(lexer.last_symbol().unwrap().token_kind, TokenKind::Eof);
+
+    // The lexer is fused, so the last symbol stays the Eof token.
+    assert!(lexer.next().is_none());
+    assert_eq!(lexer.last_symbol().unwrap().token_kind, TokenKind::Eof);
+}
+
+#[test]
+fn last_symbol_shebang() {
+    let mut lexer = LexerBuilder::new().allow_shebang(true).build("#!foo\n");
+    let symbol = lexer.next();
+    assert_eq!(lexer.last_symbol().unwrap().token_kind, TokenKind::Shebang);
+    assert_eq!(lexer.last_symbol(), symbol);
+}
+
+fn depths(input: &str) -> Vec<(String, u32)> {
+    SourcepawnLexer::new(input)
+        .with_depth()
+        .filter(|(symbol, _)| symbol.token_kind != TokenKind::Eof)
+        .map(|(symbol, depth)| (symbol.text().to_string(), depth))
+        .collect()
+}
+
+#[test]
+fn with_depth_nested() {
+    let depths: Vec<_> = depths("f(a[0]){")
+        .into_iter()
+        .map(|(_, depth)| depth)
+        .collect();
+    assert_eq!(depths, vec![0, 0, 1, 1, 2, 1, 0, 0]);
+}
+
+#[test]
+fn with_depth_unbalanced() {
+    assert_eq!(
+        depths(")}a{"),
+        vec![
+            (")".to_string(), 0),
+            ("}".to_string(), 0),
+            ("a".to_string(), 0),
+            ("{".to_string(), 0)
+        ]
+    );
+    assert_eq!(depths("{{(]x")[4], ("x".to_string(), 2));
+}
+
+#[test]
+fn map_while_significant_until_semicolon() {
+    let input = "foo // comment\n bar\\\n baz; qux";
+
+    let names: Vec<_> = SourcepawnLexer::new(input)
+        .map_while_significant(|symbol| symbol.as_identifier().map(str::to_string))
+        .collect();
+    assert_eq!(names, vec!["foo", "bar", "baz"]);
+}
+
+#[test]
+fn map_while_significant_stops_before_eof() {
+    let kinds: Vec<_> = SourcepawnLexer::new("int foo;\n")
+        .map_while_significant(|symbol| Some(symbol.token_kind))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![TokenKind::Int, TokenKind::Identifier, TokenKind::Semicolon]
+    );
+}
+
+#[test]
+fn current_text_matches_range() {
+    let input = "#define FOO(%1) %1 \\\r\n+ 1\r\nint foo = \"bar\"; // baz";
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_eq!(lexer.current_text(), "");
+    while let Some(symbol) = lexer.next() {
+        assert_eq!(lexer.current_text(), &input[symbol.range]);
+        if symbol.text_ref().is_some() && symbol.token_kind != TokenKind::Newline {
+            assert_eq!(lexer.current_text(), symbol.text());
+        }
+    }
+    assert_eq!(lexer.current_text(), "");
+}
+
+#[test]
+fn current_text_outlives_lexer() {
+    let input = "int foo;";
+
+    let texts: Vec<&str> = {
+        let mut lexer = SourcepawnLexer::new(input);
+        let mut texts = Vec::new();
+        while lexer.next().is_some() {
+            texts.push(lexer.current_text());
+        }
+        texts
+    };
+    assert_eq!(texts, vec!["int", "foo", ";", ""]);
+}
+
+#[test]
+fn validate_clean_input() {
+    let input = "#define FOO 1\nint foo = FOO; // bar\n/* baz */";
+    assert_eq!(SourcepawnLexer::validate(input), Ok(()));
+}
+
+#[test]
+fn validate_unterminated_comment() {
+    let input = "int foo;\n/* bar\nint baz;";
+    assert_eq!(
+        SourcepawnLexer::validate(input),
+        Err((
+            TextRange::new(9.into(), 24.into()),
+            LexError::UnterminatedComment
+        ))
+    );
+}
+
+#[test]
+fn validate_first_error() {
+    let input = "int $foo = \"bar;";
+    assert_eq!(
+        SourcepawnLexer::validate(input),
+        Err((
+            TextRange::new(4.into(), 5.into()),
+            LexError::UnexpectedCharacter
+        ))
+    );
+}
+
+#[test]
+fn tokens_before_matches_forward_lexing() {
+    let input = r#"#include <sourcemod>
+
+public void OnPluginStart()
+{
+    // Register the command.
+    RegConsoleCmd("sm_foo", Command_Foo);
+}
+"#;
+    let all: Vec<_> = SourcepawnLexer::new(input).collect();
+    let offset = input.find("Command_Foo").unwrap() as u32;
+
+    let before = SourcepawnLexer::new(input).tokens_before(offset.into());
+    assert_eq!(before[..], all[..before.len()]);
+    let previous: Vec<_> = before
+        .iter()
+        .rev()
+        .take(4)
+        .map(|symbol| symbol.text().to_string())
+        .collect();
+    assert_eq!(previous, vec![",", "\"sm_foo\"", "(", "RegConsoleCmd"]);
+}
+
+#[test]
+fn tokens_before_inside_token() {
+    let input = "int foo;";
+
+    let before = SourcepawnLexer::new(input).tokens_before(5.into());
+    assert_eq!(before.last().unwrap().text(), "foo");
+    assert!(SourcepawnLexer::new(input)
+        .tokens_before(0.into())
+        .is_empty());
+}
+
+#[test]
+fn tokens_before_end_of_input() {
+    let input = "int foo;";
+
+    let before = SourcepawnLexer::new(input).tokens_before(100.into());
+    let all: Vec<_> = SourcepawnLexer::new(input).without_eof().collect();
+    assert_eq!(before, all);
+}
+
+#[test]
+fn without_trivia_deltas() {
+    let input = "int foo; // bar\n  /* baz */ \\\n  qux++;";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).without_trivia().collect();
+    let texts: Vec<_> = symbols.iter().map(|symbol| symbol.text()).collect();
+    assert_eq!(texts, vec!["int", "foo", ";", "qux", "++", ";", "\0"]);
+
+    let mut cursor = DeltaCursor::new(0.into());
+    for symbol in &symbols {
+        assert_eq!(
+            cursor.next_range(symbol.delta, symbol.range.len()),
+            symbol.range
+        );
+    }
+    assert_eq!(symbols[3].delta, 32 - 8);
+}
+
+#[test]
+fn symbol_classification() {
+    let symbols: Vec<_> = LexerBuilder::new()
+        .emit_whitespace(true)
+        .build("int foo; // bar\n/* baz */")
+        .without_eof()
+        .collect();
+    let classes: Vec<_> = symbols
+        .iter()
+        .map(|symbol| (symbol.is_trivia(), symbol.is_comment(), symbol.is_keyword()))
+        .collect();
+    assert_eq!(
+        classes,
+        vec![
+            (false, false, true),
+            (true, false, false),
+            (false, false, false),
+            (false, false, false),
+            (true, false, false),
+            (true, true, false),
+            (true, false, false),
+            (true, true, false),
+        ]
+    );
+}
+
+#[test]
+fn with_gaps_reconstructs_input() {
+    let input = "  int\tfoo = 1; // bar\r\n\n  foo++; \t";
+
+    let mut text = String::new();
+    for (gap, symbol) in LexerBuilder::new()
+        .normalize_newlines(false)
+        .build(input)
+        .with_gaps(input)
+    {
+        text.push_str(gap.unwrap_or_default());
+        if symbol.token_kind != TokenKind::Eof {
+            text.push_str(&symbol.text());
+        }
+    }
+    assert_eq!(text, input);
+}
+
+#[test]
+fn with_gaps_no_gap() {
+    let input = "foo(bar)";
+
+    assert!(SourcepawnLexer::new(input)
+        .with_gaps(input)
+        .all(|(gap, _)| gap.is_none()));
+}
+
+#[test]
+fn in_preprocessor_per_symbol() {
+    let symbols: Vec<_> = SourcepawnLexer::new("#define FOO 1\nint foo;").collect();
+
+    let in_preprocessor: Vec<_> = symbols
+        .iter()
+        .map(|symbol| symbol.in_preprocessor)
+        .collect();
+    assert_eq!(
+        in_preprocessor,
+        vec![true, true, true, false, false, false, false, false]
+    );
+}
+
+#[test]
+fn recent_significant_view_as() {
+    let mut lexer = SourcepawnLexer::new("view_as /* int */ <int>(foo)");
+
+    assert_eq!(lexer.recent_significant(2).len(), 0);
+    lexer.nth(2);
+    let recent: Vec<_> = lexer.recent_significant(2).collect();
+    assert_eq!(
+        recent,
+        vec![
+            (TokenKind::ViewAs, TextRange::new(0.into(), 7.into())),
+            (
+                TokenKind::Operator(Operator::Lt),
+                TextRange::new(18.into(), 19.into())
+            )
+        ]
+    );
+    assert_eq!(
+        lexer.recent_significant(1).collect::<Vec<_>>(),
+        vec![(
+            TokenKind::Operator(Operator::Lt),
+            TextRange::new(18.into(), 19.into())
+        )]
+    );
+    assert_eq!(lexer.recent_significant(5).len(), 2);
+
+    // Older symbols are dropped once the look-behind is full.
+    lexer.next();
+    let kinds: Vec<_> = lexer.recent_significant(2).map(|(kind, _)| kind).collect();
+    assert_eq!(
+        kinds,
+        vec![TokenKind::Operator(Operator::Lt), TokenKind::Int]
+    );
+}
+
+#[test]
+fn recent_significant_disabled() {
+    let mut lexer = LexerBuilder::new().look_behind(0).build("int foo;");
+
+    lexer.nth(1);
+    assert_eq!(lexer.recent_significant(2).len(), 0);
+}
+
+/// Resumes lexing after each symbol of the input, and checks that the rest of the symbols, and the
+/// state of the lexer at the end, are the same as in a full lex.
+fn assert_resumes_like_full_lex(input: &str, builder: LexerBuilder) {
+    let mut lexer = builder.clone().build(input);
+    let mut checkpoints = Vec::new();
+    let mut full = Vec::new();
+    while let Some(symbol) = lexer.next() {
+        full.push(symbol);
+        checkpoints.push(lexer.checkpoint());
+    }
+    for (idx, checkpoint) in checkpoints[..checkpoints.len() - 1].iter().enumerate() {
+        let mut resumed_lexer = builder
+            .clone()
+            .build_from_checkpoint(input, checkpoint.clone());
+        let resumed: Vec<_> = resumed_lexer.by_ref().collect();
+        assert_eq!(resumed, full[idx + 1..]);
+        assert!(resumed
+            .iter()
+            .zip(&full[idx + 1..])
+            .all(|(resumed, full)| resumed.in_preprocessor == full.in_preprocessor));
+        assert_eq!(resumed_lexer.ctrl_char(), lexer.ctrl_char());
+        assert_eq!(resumed_lexer.rational_tag(), lexer.rational_tag());
+    }
+}
+
+#[test]
+fn from_checkpoint_matches_full_lex() {
+    let input = "#define FOO 1\nint foo = FOO;\n  #pragma semicolon 1\n\tfoo++;";
+
+    assert_resumes_like_full_lex(input, LexerBuilder::new());
+}
+
+#[test]
+fn from_checkpoint_after_pragmas() {
+    let input = "#pragma ctrlchar '%'\n#pragma rational Float\nchar c = '%n';";
+
+    assert_resumes_like_full_lex(input, LexerBuilder::new());
+}
+
+#[test]
+fn from_checkpoint_in_define_body() {
+    let input = "#define FOO(%1) %1 % 2\nint foo = FOO(1) % 2;";
+
+    assert_resumes_like_full_lex(input, LexerBuilder::new());
+
+    let mut lexer = SourcepawnLexer::new(input);
+    lexer.nth(4);
+    let checkpoint = lexer.checkpoint();
+    assert!(checkpoint.in_define);
+    let symbol = SourcepawnLexer::from_checkpoint(input, checkpoint)
+        .next()
+        .unwrap();
+    assert_eq!(symbol.token_kind, TokenKind::MacroParam);
+    assert_eq!(symbol.text(), "%1");
+}
+
+#[test]
+fn from_checkpoint_builder() {
+    let input = "int foo; /* foo bar baz */ int bar;";
+
+    let builder = LexerBuilder::new()
+        .max_token_len(8)
+        .line_relative_deltas(true);
+    assert_resumes_like_full_lex(input, builder.clone());
+    let checkpoint = Checkpoint {
+        offset: 8.into(),
+        ..Default::default()
+    };
+    let symbol = builder
+        .build_from_checkpoint(input, checkpoint)
+        .next()
+        .unwrap();
+    assert_eq!(symbol.token_kind, TokenKind::Error(LexError::TokenTooLong));
+}
+
+#[test]
+fn from_checkpoint_first_delta() {
+    let input = "int foo;\n\n  int bar;";
+
+    let checkpoint = Checkpoint {
+        offset: 9.into(),
+        ..Default::default()
+    };
+    let mut lexer = SourcepawnLexer::from_checkpoint(input, checkpoint);
+    let symbol = lexer.next().unwrap();
+    assert_eq!(symbol.token_kind, TokenKind::Newline);
+    assert_eq!(symbol.delta, 0);
+    let symbol = lexer.next().unwrap();
+    assert_eq!(symbol.token_kind, TokenKind::Int);
+    assert_eq!(symbol.range, TextRange::new(12.into(), 15.into()));
+    assert_eq!(symbol.delta, 2);
+}
+
+#[test]
+fn last_symbol_keeps_text() {
+    let input = "#pragma foo\r\nint x = 1;";
+
+    let mut lexer = LexerBuilder::new()
+        .normalize_newlines(false)
+        .dialect(Dialect::Legacy)
+        .build(input);
+    while let Some(symbol) = lexer.next() {
+        let last = lexer.last_symbol().unwrap();
+        assert_eq!(last.text_ref(), symbol.text_ref());
+        assert_eq!(last.in_preprocessor, symbol.in_preprocessor);
+    }
+}
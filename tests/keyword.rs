@@ -0,0 +1,324 @@
+mod utils;
+
+use sourcepawn_lexer::*;
+use utils::kinds;
+
+const KEYWORD_KINDS: &[TokenKind] = &[
+    TokenKind::Any,
+    TokenKind::Bool,
+    TokenKind::Break,
+    TokenKind::Case,
+    TokenKind::Char,
+    TokenKind::Class,
+    TokenKind::Const,
+    TokenKind::Continue,
+    TokenKind::Decl,
+    TokenKind::Default,
+    TokenKind::Defined,
+    TokenKind::Delete,
+    TokenKind::Do,
+    TokenKind::Else,
+    TokenKind::Enum,
+    TokenKind::False,
+    TokenKind::Float,
+    TokenKind::OldFloat,
+    TokenKind::OldString,
+    TokenKind::For,
+    TokenKind::Forward,
+    TokenKind::Funcenum,
+    TokenKind::Functag,
+    TokenKind::Function,
+    TokenKind::If,
+    TokenKind::Int,
+    TokenKind::InvalidFunction,
+    TokenKind::Methodmap,
+    TokenKind::Native,
+    TokenKind::Null,
+    TokenKind::New,
+    TokenKind::Object,
+    TokenKind::OperatorKw,
+    TokenKind::Property,
+    TokenKind::Public,
+    TokenKind::Return,
+    TokenKind::Sizeof,
+    TokenKind::Static,
+    TokenKind::Stock,
+    TokenKind::Struct,
+    TokenKind::Switch,
+    TokenKind::This,
+    TokenKind::True,
+    TokenKind::Typedef,
+    TokenKind::Typeset,
+    TokenKind::Union,
+    TokenKind::Using,
+    TokenKind::ViewAs,
+    TokenKind::Void,
+    TokenKind::While,
+    TokenKind::Nullable,
+    TokenKind::Intrinsics,
+];
+
+#[test]
+fn keyword_text_const() {
+    const IF: Option<&str> = TokenKind::If.keyword_text();
+    assert_eq!(IF, Some("if"));
+}
+
+#[test]
+fn keyword_text_not_keyword() {
+    assert_eq!(TokenKind::Identifier.keyword_text(), None);
+    assert_eq!(TokenKind::Semicolon.keyword_text(), None);
+}
+
+#[test]
+fn keyword_text_matches_text() {
+    let symbol = SourcepawnLexer::new("view_as").next().unwrap();
+    assert_eq!(
+        symbol.token_kind.keyword_text(),
+        Some(symbol.text().as_str())
+    );
+}
+
+#[test]
+fn emit_is_identifier() {
+    // Sourcepawn does not support inline assembly, so `__emit` is not a keyword and its operands
+    // are lexed like any other tokens.
+    let input = "__emit const.pri 5";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).without_eof().collect();
+    let kinds: Vec<_> = symbols.iter().map(|symbol| symbol.token_kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Identifier,
+            TokenKind::Const,
+            TokenKind::Dot,
+            TokenKind::Identifier,
+            TokenKind::Literal(Literal::IntegerLiteral)
+        ]
+    );
+    assert_eq!(symbols[0].as_identifier(), Some("__emit"));
+    assert_eq!(symbols[3].as_identifier(), Some("pri"));
+}
+
+#[test]
+fn promote_soft_keywords_modern() {
+    let input = "methodmap Foo < Handle { property int bar { } }";
+
+    let mut symbols: Vec<_> = LexerBuilder::new()
+        .dialect(Dialect::Legacy)
+        .build(input)
+        .collect();
+    assert_eq!(symbols[0].token_kind, TokenKind::Identifier);
+    assert_eq!(symbols[5].token_kind, TokenKind::Identifier);
+
+    promote_soft_keywords(&mut symbols, Dialect::Modern);
+    let expected: Vec<_> = SourcepawnLexer::new(input).collect();
+    assert_eq!(symbols, expected);
+    assert_eq!(symbols[0].token_kind, TokenKind::Methodmap);
+    assert_eq!(symbols[5].token_kind, TokenKind::Property);
+}
+
+#[test]
+fn promote_soft_keywords_legacy() {
+    let input = "methodmap Foo; int null; this";
+
+    let mut symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+    assert_eq!(symbols[0].token_kind, TokenKind::Methodmap);
+
+    promote_soft_keywords(&mut symbols, Dialect::Legacy);
+    let expected: Vec<_> = LexerBuilder::new()
+        .dialect(Dialect::Legacy)
+        .build(input)
+        .collect();
+    assert_eq!(symbols, expected);
+    assert_eq!(symbols[0].as_identifier(), Some("methodmap"));
+    assert_eq!(symbols[3].token_kind, TokenKind::Int);
+
+    // Legacy keywords stay identifiers, and other identifiers are left untouched.
+    promote_soft_keywords(&mut symbols, Dialect::Legacy);
+    assert_eq!(symbols, expected);
+    assert_eq!(symbols[1].as_identifier(), Some("Foo"));
+}
+
+#[test]
+fn legacy_callback_keywords() {
+    let input = "funcenum Foo { public(), }\nfunctag public Bar();";
+
+    let kinds: Vec<_> = SourcepawnLexer::new(input)
+        .map(|symbol| symbol.token_kind)
+        .filter(TokenKind::is_legacy_keyword)
+        .collect();
+    assert_eq!(kinds, vec![TokenKind::Funcenum, TokenKind::Functag]);
+    assert_eq!(TokenKind::Funcenum.keyword_text(), Some("funcenum"));
+}
+
+#[test]
+fn legacy_callback_keywords_prefix() {
+    let symbols: Vec<_> = SourcepawnLexer::new("funcenums functags")
+        .without_eof()
+        .collect();
+    assert!(symbols
+        .iter()
+        .all(|symbol| symbol.token_kind == TokenKind::Identifier));
+}
+
+#[test]
+fn as_keyword() {
+    let symbols: Vec<_> = SourcepawnLexer::new("int foo; return")
+        .without_eof()
+        .collect();
+    let keywords: Vec<_> = symbols.iter().filter_map(Symbol::as_keyword).collect();
+    assert_eq!(keywords, vec![TokenKind::Int, TokenKind::Return]);
+}
+
+#[test]
+fn as_keyword_legacy() {
+    let symbol = LexerBuilder::new()
+        .dialect(Dialect::Legacy)
+        .build("methodmap")
+        .next()
+        .unwrap();
+    assert_eq!(symbol.as_keyword(), None);
+}
+
+#[test]
+fn keyword_round_trip() {
+    for &kind in KEYWORD_KINDS {
+        let text = kind.keyword_text().unwrap();
+        let symbols: Vec<_> = SourcepawnLexer::new(text).without_eof().collect();
+        assert_eq!(symbols.len(), 1, "{text}");
+        assert_eq!(symbols[0].as_keyword(), Some(kind), "{text}");
+        assert_eq!(
+            TokenKind::keyword_from_str(symbols[0].text().as_str()),
+            Some(kind),
+            "{text}"
+        );
+    }
+}
+
+#[test]
+fn keyword_from_str_not_keyword() {
+    assert_eq!(TokenKind::keyword_from_str("foo"), None);
+    assert_eq!(TokenKind::keyword_from_str("Int"), None);
+    assert_eq!(TokenKind::keyword_from_str(";"), None);
+}
+
+#[test]
+fn compiler_intrinsics_adjacent() {
+    assert_eq!(
+        kinds("int __nullable__ x"),
+        vec![TokenKind::Int, TokenKind::Nullable, TokenKind::Identifier]
+    );
+    assert_eq!(
+        kinds("(__nullable__)"),
+        vec![TokenKind::LParen, TokenKind::Nullable, TokenKind::RParen]
+    );
+    assert_eq!(
+        kinds("__intrinsics__.Foo"),
+        vec![TokenKind::Intrinsics, TokenKind::Dot, TokenKind::Identifier]
+    );
+    assert_eq!(
+        kinds("__intrinsics__."),
+        vec![TokenKind::Intrinsics, TokenKind::Dot]
+    );
+}
+
+#[test]
+fn compiler_intrinsics_exact_spelling() {
+    for input in [
+        "__intrinsics__x",
+        "__intrinsics",
+        "_intrinsics__",
+        "__Intrinsics__",
+        "__nullable___",
+        "__nullable",
+        "__NULLABLE__",
+    ] {
+        assert_eq!(kinds(input), vec![TokenKind::Identifier], "{input}");
+    }
+}
+
+#[test]
+fn is_compiler_intrinsic() {
+    let intrinsics: Vec<_> = kinds("native int __intrinsics__ __nullable__ foo")
+        .into_iter()
+        .filter(TokenKind::is_compiler_intrinsic)
+        .collect();
+    assert_eq!(intrinsics, vec![TokenKind::Intrinsics, TokenKind::Nullable]);
+}
+
+#[test]
+fn keywords_list() {
+    assert!(KEYWORDS.contains(&"methodmap"));
+    assert!(KEYWORDS.contains(&"view_as"));
+    assert_eq!(KEYWORDS.len(), KEYWORD_KINDS.len());
+
+    let mut sorted = KEYWORDS.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    assert_eq!(sorted.len(), KEYWORDS.len());
+}
+
+#[test]
+fn keywords_round_trip() {
+    for &keyword in KEYWORDS {
+        let kind = TokenKind::keyword_from_str(keyword).unwrap();
+        assert_eq!(kind.keyword_text(), Some(keyword));
+        let symbol = SourcepawnLexer::new(keyword).next().unwrap();
+        assert_eq!(symbol.token_kind, kind, "{keyword}");
+    }
+}
+
+#[test]
+fn operator_overload() {
+    let kinds: Vec<_> = SourcepawnLexer::new("public bool operator+(Foo a, Foo b)")
+        .without_eof()
+        .map(|symbol| symbol.token_kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Public,
+            TokenKind::Bool,
+            TokenKind::OperatorKw,
+            TokenKind::Operator(Operator::Plus),
+            TokenKind::LParen,
+            TokenKind::Identifier,
+            TokenKind::Identifier,
+            TokenKind::Comma,
+            TokenKind::Identifier,
+            TokenKind::Identifier,
+            TokenKind::RParen,
+        ]
+    );
+    assert_eq!(TokenKind::OperatorKw.keyword_text(), Some("operator"));
+}
+
+#[test]
+fn any_keyword() {
+    let kinds: Vec<_> = SourcepawnLexer::new("any x = 5;")
+        .without_eof()
+        .map(|symbol| symbol.token_kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Any,
+            TokenKind::Identifier,
+            TokenKind::Operator(Operator::Assign),
+            TokenKind::Literal(Literal::IntegerLiteral),
+            TokenKind::Semicolon,
+        ]
+    );
+    assert!(TokenSet::TYPE_KEYWORDS.contains(TokenKind::Any));
+}
+
+#[test]
+fn any_prefix_is_identifier() {
+    for input in ["anything", "any_", "_any", "any1"] {
+        let symbol = SourcepawnLexer::new(input).next().unwrap();
+        assert_eq!(symbol.token_kind, TokenKind::Identifier);
+        assert_eq!(symbol.text(), input);
+    }
+}
@@ -0,0 +1,51 @@
+mod utils;
+
+use insta::assert_json_snapshot;
+use sourcepawn_lexer::*;
+use utils::collect_tokens;
+
+#[test]
+fn leaving_simple() {
+    let input = r#"#leaving "include/foo.inc"
+int foo;
+"#;
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_json_snapshot!(collect_tokens(&mut lexer));
+}
+
+#[test]
+fn leaving_in_preprocessor() {
+    let mut lexer = SourcepawnLexer::new("#leaving\nint foo;");
+
+    let symbol = lexer.next().unwrap();
+    assert_eq!(
+        symbol.token_kind,
+        TokenKind::PreprocDir(PreprocDir::MLeaving)
+    );
+    assert!(symbol.in_preprocessor);
+    assert_eq!(symbol.directive_argument(), None);
+    let symbol = lexer.next().unwrap();
+    assert_eq!(symbol.token_kind, TokenKind::Newline);
+    assert!(!symbol.in_preprocessor);
+}
+
+#[test]
+fn leaving_line_continuation() {
+    let mut lexer = SourcepawnLexer::new("#leaving \\\n\"foo.inc\"\nint foo;");
+
+    let symbol = lexer.next().unwrap();
+    assert!(symbol.in_preprocessor);
+    assert_eq!(symbol.directive_argument(), Some("\"foo.inc\"".to_string()));
+}
+
+#[test]
+fn directive_argument_pragma() {
+    let symbol = SourcepawnLexer::new("#pragma semicolon /* comment */ 1\n")
+        .next()
+        .unwrap();
+    assert_eq!(
+        symbol.directive_argument(),
+        Some("semicolon  1".to_string())
+    );
+}
@@ -0,0 +1,184 @@
+use sourcepawn_lexer::*;
+
+fn lines(input: &str) -> Vec<Vec<TokenKind>> {
+    let symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+    group_by_line(&symbols)
+        .into_iter()
+        .map(|line| line.iter().map(|symbol| symbol.token_kind).collect())
+        .collect()
+}
+
+#[test]
+fn group_by_line_simple() {
+    let input = "int foo;\nfoo = 1;\n";
+
+    let lines = lines(input);
+    assert_eq!(lines.len(), 3);
+    assert_eq!(
+        lines[0],
+        vec![
+            TokenKind::Int,
+            TokenKind::Identifier,
+            TokenKind::Semicolon,
+            TokenKind::Newline
+        ]
+    );
+    assert_eq!(lines[2], vec![TokenKind::Eof]);
+}
+
+#[test]
+fn group_by_line_continuation() {
+    let input = "int foo = \\\n    1;\nint bar;";
+
+    let lines = lines(input);
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].contains(&TokenKind::LineContinuation));
+    assert_eq!(lines[0].last(), Some(&TokenKind::Newline));
+    assert_eq!(
+        lines[0]
+            .iter()
+            .filter(|kind| **kind == TokenKind::Semicolon)
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn group_by_line_no_trailing_newline() {
+    let input = "int foo;\nint bar;";
+
+    let lines = lines(input);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(
+        lines[1],
+        vec![
+            TokenKind::Int,
+            TokenKind::Identifier,
+            TokenKind::Semicolon,
+            TokenKind::Eof
+        ]
+    );
+}
+
+#[test]
+fn group_by_line_empty() {
+    assert!(group_by_line(&[]).is_empty());
+}
+
+#[test]
+fn line_index_crlf() {
+    let input = "int foo;\r\n\r\nint bar;";
+
+    let index = LineIndex::new(input);
+    assert_eq!(index.line_count(), 3);
+    assert_eq!(index.line_col(8.into()), LineCol { line: 0, col: 8 });
+    assert_eq!(index.line_col(12.into()), LineCol { line: 2, col: 0 });
+    assert_eq!(index.line_col(16.into()), LineCol { line: 2, col: 4 });
+}
+
+#[test]
+fn line_index_continuation_resets_line() {
+    let input = "#define FOO \\\n    1 + \\\r\n2\nint bar;";
+
+    let index = LineIndex::new(input);
+    assert_eq!(index.line_count(), 4);
+    let positions: Vec<_> = SourcepawnLexer::new(input)
+        .filter(|symbol| symbol.token_kind == TokenKind::Literal(Literal::IntegerLiteral))
+        .map(|symbol| index.line_col(symbol.range.start()))
+        .collect();
+    assert_eq!(
+        positions,
+        vec![LineCol { line: 1, col: 4 }, LineCol { line: 2, col: 0 }]
+    );
+    assert_eq!(index.line_col(27.into()), LineCol { line: 3, col: 0 });
+}
+
+#[test]
+fn line_index_continuation_does_not_reset_line() {
+    let input = "#define FOO \\\n    1 + \\\r\n2\nint bar;";
+
+    let index = LineIndex::new(input).continuation_resets_line(false);
+    assert_eq!(index.line_count(), 2);
+    let positions: Vec<_> = SourcepawnLexer::new(input)
+        .filter(|symbol| symbol.token_kind == TokenKind::Literal(Literal::IntegerLiteral))
+        .map(|symbol| index.line_col(symbol.range.start()))
+        .collect();
+    assert_eq!(
+        positions,
+        vec![LineCol { line: 0, col: 18 }, LineCol { line: 0, col: 25 }]
+    );
+    assert_eq!(index.line_col(27.into()), LineCol { line: 1, col: 0 });
+}
+
+#[test]
+fn text_between_parentheses() {
+    let input = "Foo(bar(1), \"(\");";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).with_depth().collect();
+    let (open, depth) = &symbols[1];
+    let (close, _) = symbols[2..]
+        .iter()
+        .find(|(symbol, d)| symbol.token_kind == TokenKind::RParen && d == depth)
+        .unwrap();
+    assert_eq!(text_between(input, open, close), "(bar(1), \"(\")");
+    assert_eq!(text_between_exclusive(input, open, close), "bar(1), \"(\"");
+}
+
+#[test]
+fn text_between_empty() {
+    let input = "foo()";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+    assert_eq!(text_between(input, &symbols[1], &symbols[2]), "()");
+    assert_eq!(text_between_exclusive(input, &symbols[1], &symbols[2]), "");
+}
+
+#[test]
+fn text_between_reversed() {
+    let input = "foo(1)";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+    assert_eq!(text_between(input, &symbols[3], &symbols[1]), "");
+    assert_eq!(text_between_exclusive(input, &symbols[2], &symbols[2]), "");
+    assert_eq!(text_between("foo", &symbols[1], &symbols[3]), "");
+}
+
+fn positions(input: &str) -> Vec<(LineCol, LineCol)> {
+    let index = LineIndex::new(input);
+    SourcepawnLexer::new(input)
+        .map(|symbol| (symbol.start_position(&index), symbol.end_position(&index)))
+        .collect()
+}
+
+fn line_col(line: u32, col: u32) -> LineCol {
+    LineCol { line, col }
+}
+
+#[test]
+fn positions_multi_line() {
+    let input = "int foo;\r\n\n  bar";
+
+    assert_eq!(
+        positions(input),
+        vec![
+            (line_col(0, 0), line_col(0, 3)),
+            (line_col(0, 4), line_col(0, 7)),
+            (line_col(0, 7), line_col(0, 8)),
+            (line_col(0, 8), line_col(1, 0)),
+            (line_col(1, 0), line_col(2, 0)),
+            (line_col(2, 2), line_col(2, 5)),
+            (line_col(2, 5), line_col(2, 5)),
+        ]
+    );
+}
+
+#[test]
+fn positions_unicode_pragma() {
+    let input = "#pragma deprecated \"Устаревшая функция.\"\nint foo;";
+
+    let positions = positions(input);
+    // Columns are in bytes: each cyrillic letter is 2 bytes long.
+    assert_eq!(positions[0], (line_col(0, 0), line_col(0, 57)));
+    assert_eq!(positions[1], (line_col(0, 57), line_col(1, 0)));
+    assert_eq!(positions[2], (line_col(1, 0), line_col(1, 3)));
+}
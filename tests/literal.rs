@@ -0,0 +1,219 @@
+mod utils;
+
+use sourcepawn_lexer::*;
+use utils::kinds;
+
+fn to_int(input: &str) -> Option<u32> {
+    SourcepawnLexer::new(input).next().unwrap().to_int()
+}
+
+#[test]
+fn hex_to_int() {
+    assert_eq!(to_int("0x0"), Some(0));
+    assert_eq!(to_int("0x1A"), Some(0x1A));
+    assert_eq!(to_int("0xFF"), Some(0xFF));
+}
+
+#[test]
+fn hex_to_int_separators() {
+    assert_eq!(to_int("0xdead_beef"), Some(0xdead_beef));
+}
+
+#[test]
+fn hex_to_int_mixed_case() {
+    assert_eq!(to_int("0xaBcD"), Some(0xabcd));
+    assert_eq!(to_int("0xFfFfFfFf"), Some(u32::MAX));
+}
+
+#[test]
+fn hex_to_int_overflow() {
+    assert_eq!(to_int("0x1_0000_0000"), None);
+}
+
+#[test]
+fn binary_to_int() {
+    assert_eq!(to_int("0b0"), Some(0));
+    assert_eq!(to_int("0b1010"), Some(0b1010));
+}
+
+#[test]
+fn binary_to_int_separators() {
+    assert_eq!(to_int("0b1111_0000"), Some(0b1111_0000));
+}
+
+#[test]
+fn binary_to_int_invalid() {
+    let symbol = Symbol::new(
+        TokenKind::Literal(Literal::BinaryLiteral),
+        Some("0b1012"),
+        TextRange::default(),
+        0,
+    );
+    assert_eq!(symbol.to_int(), None);
+}
+
+#[test]
+fn octal_to_int() {
+    assert_eq!(to_int("0o0"), Some(0));
+    assert_eq!(to_int("0o777"), Some(0o777));
+}
+
+#[test]
+fn octal_to_int_separators() {
+    assert_eq!(to_int("0o1_000"), Some(0o1000));
+}
+
+#[test]
+fn octal_to_int_invalid() {
+    let symbol = Symbol::new(
+        TokenKind::Literal(Literal::OctodecimalLiteral),
+        Some("0o8"),
+        TextRange::default(),
+        0,
+    );
+    assert_eq!(symbol.to_int(), None);
+}
+
+#[test]
+fn char_to_int() {
+    assert_eq!(to_int("'a'"), Some(97));
+    assert_eq!(to_int("'é'"), Some(0xe9));
+}
+
+#[test]
+fn char_to_int_wide_chars() {
+    // The value is the Unicode scalar value of the character, not the sum of its UTF-8 bytes.
+    assert_eq!(to_int("'é'"), Some(233));
+    assert_eq!(to_int("'€'"), Some(8364));
+    assert_eq!(to_int("'😀'"), Some(0x1f600));
+}
+
+#[test]
+fn char_to_int_escapes() {
+    assert_eq!(to_int(r"'\n'"), Some(10));
+    assert_eq!(to_int(r"'\t'"), Some(9));
+    assert_eq!(to_int(r"'\x41'"), Some(0x41));
+    assert_eq!(to_int(r"'\''"), Some(39));
+}
+
+#[test]
+fn char_to_int_invalid() {
+    assert_eq!(to_int("''"), None);
+    assert_eq!(to_int("'ab'"), None);
+    assert_eq!(to_int(r"'\q'"), None);
+}
+
+fn to_float(input: &str) -> Option<f64> {
+    SourcepawnLexer::new(input).next().unwrap().to_float()
+}
+
+#[test]
+fn float_to_float() {
+    assert_eq!(to_float("1.0"), Some(1.0));
+    assert_eq!(to_float("1.0e10"), Some(1.0e10));
+    assert_eq!(to_float("1.0e-10"), Some(1.0e-10));
+    assert_eq!(to_float("100_00.000_1e-10"), Some(10000.0001e-10));
+    assert_eq!(to_float(".5"), Some(0.5));
+}
+
+#[test]
+fn hex_float_lexing() {
+    for input in ["0x1.8p3", "0x1p-2", "0x.8P+1", "0xA.p0"] {
+        assert_eq!(
+            kinds(input),
+            vec![TokenKind::Literal(Literal::HexFloatLiteral)],
+            "{input}"
+        );
+    }
+    assert_eq!(kinds("0x1A"), vec![TokenKind::Literal(Literal::HexLiteral)]);
+    // The exponent is required.
+    assert_eq!(
+        kinds("0x1p"),
+        vec![
+            TokenKind::Literal(Literal::HexLiteral),
+            TokenKind::Identifier
+        ]
+    );
+}
+
+#[test]
+fn hex_float_to_float() {
+    assert_eq!(to_float("0x1.8p3"), Some(12.0));
+    assert_eq!(to_float("0x1p-2"), Some(0.25));
+    assert_eq!(to_float("0x.8P+1"), Some(1.0));
+    assert_eq!(to_float("0xA.p0"), Some(10.0));
+    assert_eq!(to_float("0x1_0.0p0"), Some(16.0));
+    assert_eq!(to_int("0x1.8p3"), Some(12));
+}
+
+#[test]
+fn integer_to_float() {
+    assert_eq!(to_float("1_000"), Some(1000.0));
+    assert_eq!(to_float("0xFF"), Some(255.0));
+}
+
+#[test]
+fn not_number_to_float() {
+    assert_eq!(to_float("\"1.0\""), None);
+    assert_eq!(to_float("'1'"), None);
+    assert_eq!(to_float("foo"), None);
+}
+
+fn to_i64(input: &str) -> Option<i64> {
+    SourcepawnLexer::new(input).next().unwrap().to_i64()
+}
+
+#[test]
+fn integer_to_i64() {
+    assert_eq!(to_i64("4000000000"), Some(4_000_000_000));
+    assert_eq!(to_i64("1_000"), Some(1000));
+    assert_eq!(to_int("5000000000"), None);
+    assert_eq!(to_i64("5000000000"), Some(5_000_000_000));
+}
+
+#[test]
+fn radix_to_i64() {
+    assert_eq!(to_i64("0xFFFFFFFF"), Some(0xFFFF_FFFF));
+    assert_eq!(to_i64("0x7FFFFFFFFFFFFFFF"), Some(i64::MAX));
+    assert_eq!(
+        to_i64("0b1_0000_0000_0000_0000_0000_0000_0000_0000"),
+        Some(1 << 32)
+    );
+    assert_eq!(to_i64("0o777"), Some(0o777));
+}
+
+#[test]
+fn invalid_to_i64() {
+    assert_eq!(to_i64("0x8000000000000000"), None);
+    assert_eq!(to_i64("1.0"), None);
+    assert_eq!(to_i64("'a'"), None);
+    assert_eq!(to_i64("foo"), None);
+}
+
+#[test]
+fn quote_boundaries() {
+    assert_eq!(
+        kinds(r#""""#),
+        vec![TokenKind::Literal(Literal::StringLiteral)]
+    );
+    assert_eq!(kinds("''"), vec![TokenKind::Error(LexError::EmptyChar)]);
+    assert_eq!(
+        kinds(r"'\''"),
+        vec![TokenKind::Literal(Literal::CharLiteral)]
+    );
+    assert_eq!(
+        kinds(r#""\"""#),
+        vec![TokenKind::Literal(Literal::StringLiteral)]
+    );
+}
+
+#[test]
+fn empty_char_text() {
+    let symbol = SourcepawnLexer::new("char c = '';").nth(3).unwrap();
+    assert_eq!(symbol.token_kind, TokenKind::Error(LexError::EmptyChar));
+    assert_eq!(symbol.text(), "''");
+    assert_eq!(
+        SourcepawnLexer::validate("char c = '';"),
+        Err((TextRange::new(9.into(), 11.into()), LexError::EmptyChar))
+    );
+}
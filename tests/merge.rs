@@ -0,0 +1,80 @@
+use sourcepawn_lexer::{merge_adjacent, PreprocDir, SourcepawnLexer, Symbol, TextRange, TokenKind};
+
+const ENUM_STRUCT: (&[TokenKind], TokenKind) =
+    (&[TokenKind::Enum, TokenKind::Struct], TokenKind::EnumStruct);
+
+#[test]
+fn merge_enum_struct() {
+    let input = "int x;\nenum  struct Foo {}";
+
+    let symbols: Vec<Symbol> = SourcepawnLexer::new(input).collect();
+    let merged = merge_adjacent(&symbols, &[ENUM_STRUCT]);
+    assert_eq!(merged.len(), symbols.len() - 1);
+    assert_eq!(merged[..4], symbols[..4]);
+    assert_eq!(merged[4].token_kind, TokenKind::EnumStruct);
+    assert_eq!(merged[4].text(), "enum  struct");
+    assert_eq!(merged[4].range, TextRange::new(7.into(), 19.into()));
+    assert_eq!(merged[4].delta, symbols[4].delta);
+    assert_eq!(merged[5..], symbols[6..]);
+}
+
+#[test]
+fn merge_not_adjacent() {
+    let input = "enum /* foo */ struct Foo {}\nenum\nstruct";
+
+    let symbols: Vec<Symbol> = SourcepawnLexer::new(input).collect();
+    assert_eq!(merge_adjacent(&symbols, &[ENUM_STRUCT]), symbols);
+}
+
+#[test]
+fn merge_first_rule_wins() {
+    let input = "enum struct enum";
+
+    let symbols: Vec<Symbol> = SourcepawnLexer::new(input).collect();
+    let merged = merge_adjacent(
+        &symbols,
+        &[
+            (
+                &[TokenKind::Enum, TokenKind::Struct, TokenKind::Enum],
+                TokenKind::Identifier,
+            ),
+            ENUM_STRUCT,
+        ],
+    );
+    let kinds: Vec<_> = merged.iter().map(|symbol| symbol.token_kind).collect();
+    assert_eq!(kinds, vec![TokenKind::Identifier, TokenKind::Eof]);
+    assert_eq!(merged[0].text(), "enum struct enum");
+}
+
+#[test]
+fn merge_three_symbols() {
+    let input = "#pragma semicolon 1\nenum struct";
+
+    let symbols: Vec<Symbol> = SourcepawnLexer::new(input).collect();
+    let merged = merge_adjacent(
+        &symbols,
+        &[(
+            &[
+                TokenKind::PreprocDir(PreprocDir::MPragma),
+                TokenKind::Newline,
+                TokenKind::Enum,
+            ],
+            TokenKind::Identifier,
+        )],
+    );
+    assert_eq!(merged[0].token_kind, TokenKind::Identifier);
+    assert_eq!(merged[0].text(), "#pragma semicolon 1\nenum");
+    assert_eq!(merged[1].token_kind, TokenKind::Struct);
+}
+
+#[test]
+fn merge_synthetic_symbols() {
+    let symbols = vec![
+        Symbol::new(TokenKind::Enum, None, TextRange::empty(3.into()), 1),
+        Symbol::new(TokenKind::Struct, None, TextRange::empty(5.into()), 2),
+    ];
+    let merged = merge_adjacent(&symbols, &[ENUM_STRUCT]);
+    assert_eq!(merged.len(), 1);
+    assert!(merged[0].is_synthetic());
+    assert_eq!(merged[0].range, TextRange::empty(3.into()));
+}
@@ -0,0 +1,70 @@
+use sourcepawn_lexer::*;
+
+/// Returns the modifiers of the first identifier named `name`.
+fn modifiers_of(input: &str, name: &str) -> ModifierSet {
+    let symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+    let idx = symbols
+        .iter()
+        .position(|symbol| symbol.as_identifier() == Some(name))
+        .unwrap();
+    infer_modifiers(&symbols[..idx])
+}
+
+#[test]
+fn infer_modifiers_const_static() {
+    let modifiers = modifiers_of("const static int foo = 1;", "foo");
+    assert_eq!(
+        modifiers,
+        ModifierSet::new(&[Modifier::Const, Modifier::Static])
+    );
+}
+
+#[test]
+fn infer_modifiers_array_and_tags() {
+    assert_eq!(
+        modifiers_of("static const char foo[] = \"bar\";", "foo"),
+        ModifierSet::new(&[Modifier::Const, Modifier::Static])
+    );
+    assert_eq!(
+        modifiers_of("static const char[] foo = \"bar\";", "foo"),
+        ModifierSet::new(&[Modifier::Const, Modifier::Static])
+    );
+    assert_eq!(
+        modifiers_of("static Handle foo;", "foo"),
+        ModifierSet::new(&[Modifier::Static])
+    );
+    assert_eq!(
+        modifiers_of("new const Float:foo = 1.0;", "foo"),
+        ModifierSet::new(&[Modifier::Const])
+    );
+}
+
+#[test]
+fn infer_modifiers_functions() {
+    assert_eq!(
+        modifiers_of("native void Foo();", "Foo"),
+        ModifierSet::new(&[Modifier::Native])
+    );
+    assert_eq!(
+        modifiers_of("public void OnPluginStart() {}", "OnPluginStart"),
+        ModifierSet::new(&[Modifier::Public])
+    );
+    assert_eq!(
+        modifiers_of("void Foo(const char[] bar) {}", "bar"),
+        ModifierSet::new(&[Modifier::Const])
+    );
+}
+
+#[test]
+fn infer_modifiers_stops_at_boundary() {
+    // The search stops at the previous statement and at commas.
+    assert!(modifiers_of("const int foo = 1;\nint bar;", "bar").is_empty());
+    assert!(modifiers_of("const int foo, bar;", "bar").is_empty());
+    assert!(modifiers_of("int foo;", "foo").is_empty());
+}
+
+#[test]
+fn modifier_from_kind() {
+    assert_eq!(Modifier::from_kind(TokenKind::Stock), Some(Modifier::Stock));
+    assert_eq!(Modifier::from_kind(TokenKind::Int), None);
+}
@@ -0,0 +1,225 @@
+mod utils;
+
+use insta::assert_json_snapshot;
+use sourcepawn_lexer::*;
+use utils::{collect_tokens, kinds};
+
+#[test]
+fn operator_run_plus() {
+    assert_eq!(
+        kinds("+++a"),
+        vec![
+            TokenKind::Operator(Operator::Increment),
+            TokenKind::Operator(Operator::Plus),
+            TokenKind::Identifier
+        ]
+    );
+}
+
+#[test]
+fn operator_run_minus() {
+    assert_eq!(
+        kinds("----a"),
+        vec![
+            TokenKind::Operator(Operator::Decrement),
+            TokenKind::Operator(Operator::Decrement),
+            TokenKind::Identifier
+        ]
+    );
+}
+
+#[test]
+fn operator_run_gt() {
+    assert_eq!(
+        kinds(">>>>"),
+        vec![
+            TokenKind::Operator(Operator::Ushr),
+            TokenKind::Operator(Operator::Gt)
+        ]
+    );
+}
+
+#[test]
+fn operator_run_lt() {
+    assert_eq!(
+        kinds("<<<"),
+        vec![
+            TokenKind::Operator(Operator::Shl),
+            TokenKind::Operator(Operator::Lt)
+        ]
+    );
+}
+
+#[test]
+fn operator_runs() {
+    let input = r#"a+++b;
+c----d;
+e>>>>f;
+g<<<h;
+"#;
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_json_snapshot!(collect_tokens(&mut lexer));
+}
+
+#[test]
+fn shift_run_ushr_gt() {
+    assert_eq!(
+        kinds("a>>>>b"),
+        vec![
+            TokenKind::Identifier,
+            TokenKind::Operator(Operator::Ushr),
+            TokenKind::Operator(Operator::Gt),
+            TokenKind::Identifier
+        ]
+    );
+}
+
+#[test]
+fn shift_assign_ushr() {
+    assert_eq!(
+        kinds("a>>>=b"),
+        vec![
+            TokenKind::Identifier,
+            TokenKind::Operator(Operator::AssignUshr),
+            TokenKind::Identifier
+        ]
+    );
+    assert_eq!(
+        kinds(">>>>="),
+        vec![
+            TokenKind::Operator(Operator::Ushr),
+            TokenKind::Operator(Operator::Ge)
+        ]
+    );
+}
+
+#[test]
+fn shift_separated_by_whitespace() {
+    assert_eq!(
+        kinds(">> >"),
+        vec![
+            TokenKind::Operator(Operator::Shr),
+            TokenKind::Operator(Operator::Gt)
+        ]
+    );
+}
+
+#[test]
+fn shift_operators() {
+    assert_eq!(
+        kinds("> >> >>> >>= >>>="),
+        vec![
+            TokenKind::Operator(Operator::Gt),
+            TokenKind::Operator(Operator::Shr),
+            TokenKind::Operator(Operator::Ushr),
+            TokenKind::Operator(Operator::AssignShr),
+            TokenKind::Operator(Operator::AssignUshr)
+        ]
+    );
+}
+
+#[test]
+fn colon_runs() {
+    assert_eq!(kinds(":"), vec![TokenKind::Colon]);
+    assert_eq!(kinds("::"), vec![TokenKind::Scope]);
+    assert_eq!(kinds(":::"), vec![TokenKind::Scope, TokenKind::Colon]);
+    assert_eq!(kinds("::::"), vec![TokenKind::Scope, TokenKind::Scope]);
+    assert_eq!(kinds(": :"), vec![TokenKind::Colon, TokenKind::Colon]);
+}
+
+#[test]
+fn colon_ternary() {
+    assert_eq!(
+        kinds("a?b:c"),
+        vec![
+            TokenKind::Identifier,
+            TokenKind::Qmark,
+            TokenKind::Identifier,
+            TokenKind::Colon,
+            TokenKind::Identifier
+        ]
+    );
+}
+
+#[test]
+fn colon_tag() {
+    assert_eq!(
+        kinds("Float:a=b?c:Float:d"),
+        vec![
+            TokenKind::OldFloat,
+            TokenKind::Colon,
+            TokenKind::Identifier,
+            TokenKind::Operator(Operator::Assign),
+            TokenKind::Identifier,
+            TokenKind::Qmark,
+            TokenKind::Identifier,
+            TokenKind::Colon,
+            TokenKind::OldFloat,
+            TokenKind::Colon,
+            TokenKind::Identifier
+        ]
+    );
+}
+
+#[test]
+fn expand_compound_assignment() {
+    assert_eq!(
+        Operator::AssignShr.expand_compound_assignment(),
+        Some((Operator::Shr, Operator::Assign))
+    );
+    assert_eq!(
+        Operator::AssignUshr.expand_compound_assignment(),
+        Some((Operator::Ushr, Operator::Assign))
+    );
+    assert_eq!(
+        Operator::AssignAdd.expand_compound_assignment(),
+        Some((Operator::Plus, Operator::Assign))
+    );
+    assert_eq!(Operator::Assign.expand_compound_assignment(), None);
+    assert_eq!(Operator::Plus.expand_compound_assignment(), None);
+}
+
+#[test]
+fn expand_compound_assignment_text() {
+    // The spelling of every compound assignment is its base operator followed by `=`.
+    for symbol in SourcepawnLexer::new("+= -= *= /= %= &= |= ^= >>= >>>= <<=").without_eof() {
+        let TokenKind::Operator(op) = symbol.token_kind else {
+            panic!("expected an operator");
+        };
+        let (base, assign) = op.expand_compound_assignment().unwrap();
+        assert_eq!(
+            format!("{}{}", base.text(), assign.text()),
+            symbol.text().as_str()
+        );
+    }
+}
+
+#[test]
+fn assign_ushr_text() {
+    let input = "a >>>= b";
+
+    let symbol = SourcepawnLexer::new(input).nth(1).unwrap();
+    assert_eq!(symbol.token_kind, TokenKind::Operator(Operator::AssignUshr));
+    assert_eq!(symbol.text(), ">>>=");
+    assert_eq!(Operator::AssignUshr.text(), ">>>=");
+}
+
+#[test]
+#[allow(deprecated)]
+fn assign_ushl_alias() {
+    assert!(matches!(Operator::AssignUshr, Operator::AssignUshl));
+}
+
+#[test]
+fn access_operators() {
+    assert!(TokenKind::Dot.is_access_operator());
+    assert!(TokenKind::Scope.is_access_operator());
+    assert!(!TokenKind::Colon.is_access_operator());
+
+    let accesses: Vec<_> = SourcepawnLexer::new("a.b::c:d")
+        .filter(Symbol::is_access_operator)
+        .map(|symbol| symbol.token_kind)
+        .collect();
+    assert_eq!(accesses, vec![TokenKind::Dot, TokenKind::Scope]);
+}
@@ -0,0 +1,55 @@
+use sourcepawn_lexer::*;
+
+#[test]
+fn peek_does_not_consume() {
+    let mut lexer = PeekableSourcepawnLexer::new(SourcepawnLexer::new("int foo;"));
+
+    assert_eq!(lexer.peek().unwrap().token_kind, TokenKind::Int);
+    assert_eq!(lexer.peek().unwrap().token_kind, TokenKind::Int);
+    assert_eq!(lexer.peek_nth(2).unwrap().token_kind, TokenKind::Semicolon);
+
+    let symbols: Vec<_> = lexer.collect();
+    let expected: Vec<_> = SourcepawnLexer::new("int foo;").collect();
+    assert_eq!(symbols, expected);
+}
+
+#[test]
+fn peek_past_eof() {
+    let mut lexer = PeekableSourcepawnLexer::new(SourcepawnLexer::new("foo"));
+
+    assert!(lexer.peek_nth(2).is_none());
+    assert_eq!(lexer.peek_nth(1).unwrap().token_kind, TokenKind::Eof);
+    assert_eq!(lexer.next().unwrap().token_kind, TokenKind::Identifier);
+    assert_eq!(lexer.next().unwrap().token_kind, TokenKind::Eof);
+    assert!(lexer.peek().is_none());
+    assert!(lexer.next().is_none());
+}
+
+#[test]
+fn peek_across_define() {
+    let input = "#define FOO 1\nint foo;";
+    let mut lexer = PeekableSourcepawnLexer::new(SourcepawnLexer::new(input));
+
+    assert_eq!(
+        lexer.next().unwrap().token_kind,
+        TokenKind::PreprocDir(PreprocDir::MDefine)
+    );
+    assert!(lexer.in_preprocessor());
+
+    // Peeking past the end of the directive does not change the consumed position.
+    assert_eq!(lexer.peek_nth(3).unwrap().token_kind, TokenKind::Int);
+    assert!(lexer.in_preprocessor());
+    assert!(!lexer.inner().in_preprocessor());
+
+    let mut in_preprocessor = Vec::new();
+    while let Some(symbol) = lexer.next() {
+        in_preprocessor.push((symbol.token_kind, lexer.in_preprocessor()));
+    }
+    let mut expected = Vec::new();
+    let mut reference = SourcepawnLexer::new(input);
+    reference.next();
+    while let Some(symbol) = reference.next() {
+        expected.push((symbol.token_kind, reference.in_preprocessor()));
+    }
+    assert_eq!(in_preprocessor, expected);
+}
@@ -103,3 +103,233 @@ fn pragma_unicode() {
     let mut lexer = SourcepawnLexer::new(input);
     assert_json_snapshot!(collect_tokens(&mut lexer));
 }
+
+#[test]
+fn pragma_rational() {
+    let input = r#"#pragma rational Float
+new Float:foo;"#;
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_eq!(lexer.rational_tag(), None);
+    lexer.next();
+    assert_eq!(lexer.rational_tag(), Some("Float"));
+}
+
+#[test]
+fn pragma_rational_precision() {
+    let mut lexer = SourcepawnLexer::new("#pragma rational Float(3)\n");
+    lexer.next();
+    assert_eq!(lexer.rational_tag(), Some("Float"));
+}
+
+#[test]
+fn extract_trailing_comment_line_comment() {
+    let symbol = Symbol::new(
+        TokenKind::PreprocDir(PreprocDir::MPragma),
+        Some("#pragma deprecated foo //bar"),
+        TextRange::new(4.into(), 32.into()),
+        1,
+    );
+
+    let (pragma, comment) = symbol.extract_trailing_comment().unwrap();
+    assert_eq!(
+        pragma.token_kind,
+        TokenKind::PreprocDir(PreprocDir::MPragma)
+    );
+    assert_eq!(pragma.text(), "#pragma deprecated foo ");
+    assert_eq!(pragma.range, TextRange::new(4.into(), 27.into()));
+    assert_eq!(pragma.delta, 1);
+    assert_eq!(comment.token_kind, TokenKind::Comment(Comment::LineComment));
+    assert_eq!(comment.text(), "//bar");
+    assert_eq!(comment.range, TextRange::new(27.into(), 32.into()));
+    assert_eq!(comment.delta, 0);
+}
+
+#[test]
+fn extract_trailing_comment_block_comment() {
+    // A block comment at the end of the input is part of the pragma.
+    let input = "#pragma deprecated foo /* */";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+    assert_eq!(symbols.len(), 2);
+    let (pragma, comment) = symbols[0].extract_trailing_comment().unwrap();
+    assert_eq!(pragma.text(), "#pragma deprecated foo ");
+    assert_eq!(pragma.range, TextRange::new(0.into(), 23.into()));
+    assert_eq!(
+        comment.token_kind,
+        TokenKind::Comment(Comment::BlockComment)
+    );
+    assert_eq!(comment.text(), "/* */");
+    assert_eq!(comment.range, TextRange::new(23.into(), 28.into()));
+}
+
+#[test]
+fn extract_trailing_comment_inner_comment() {
+    let symbol = SourcepawnLexer::new("#pragma deprecated foo /* */ bar\n")
+        .next()
+        .unwrap();
+    assert_eq!(symbol.extract_trailing_comment(), None);
+}
+
+#[test]
+fn extract_trailing_comment_in_string() {
+    let symbol = SourcepawnLexer::new("#pragma deprecated \"/* foo */\"")
+        .next()
+        .unwrap();
+    assert_eq!(symbol.extract_trailing_comment(), None);
+}
+
+#[test]
+fn extract_trailing_comment_not_directive() {
+    let symbol = SourcepawnLexer::new("foo").next().unwrap();
+    assert_eq!(symbol.extract_trailing_comment(), None);
+}
+
+#[test]
+fn message_range_line_continuation() {
+    let input = "#pragma deprecated Use \\\n   Bar instead\nint foo;";
+
+    let symbol = SourcepawnLexer::new(input).next().unwrap();
+    assert_eq!(symbol.range, TextRange::new(0.into(), 39.into()));
+    let message_range = symbol.message_range().unwrap();
+    assert_eq!(&input[message_range], "Use \\\n   Bar instead");
+    assert_eq!(
+        symbol.directive_argument(),
+        Some("deprecated Use    Bar instead".to_string())
+    );
+}
+
+#[test]
+fn message_range_continuation_before_message() {
+    let input = "#pragma deprecated \\\r\n  Use Bar ";
+
+    let symbol = SourcepawnLexer::new(input).next().unwrap();
+    assert_eq!(symbol.range, TextRange::new(0.into(), 32.into()));
+    assert_eq!(&input[symbol.message_range().unwrap()], "Use Bar");
+}
+
+#[test]
+fn message_range_none() {
+    let message_range = |input| SourcepawnLexer::new(input).next().unwrap().message_range();
+    assert_eq!(message_range("#pragma deprecated"), None);
+    assert_eq!(message_range("#pragma deprecated   \n"), None);
+    assert_eq!(message_range("#pragma deprecatedfoo"), None);
+    assert_eq!(message_range("#pragma semicolon 1"), None);
+    assert_eq!(message_range("deprecated"), None);
+}
+
+fn pragma(input: &str) -> Option<(String, Option<String>)> {
+    let pragma = SourcepawnLexer::new(input).next().unwrap().as_pragma()?;
+    Some((
+        pragma.name.to_string(),
+        pragma.value.map(|value| value.to_string()),
+    ))
+}
+
+#[test]
+fn as_pragma_simple() {
+    assert_eq!(
+        pragma("#pragma semicolon 1\n"),
+        Some(("semicolon".to_string(), Some("1".to_string())))
+    );
+    assert_eq!(
+        pragma("#pragma dynamic"),
+        Some(("dynamic".to_string(), None))
+    );
+}
+
+#[test]
+fn as_pragma_comments() {
+    let expected = Some(("deprecated".to_string(), Some("foo".to_string())));
+    assert_eq!(pragma("#pragma deprecated foo //bar\n"), expected);
+    assert_eq!(pragma("#pragma deprecated foo /* */\n"), expected);
+    assert_eq!(
+        pragma("#pragma deprecated foo /* */ bar\n"),
+        Some(("deprecated".to_string(), Some("foo  bar".to_string())))
+    );
+    // A multiline block comment ends the directive.
+    assert_eq!(pragma("#pragma deprecated foo /*\n*/ bar\n"), expected);
+}
+
+#[test]
+fn as_pragma_line_continuation() {
+    let expected = Some(("deprecated".to_string(), Some("foo bar".to_string())));
+    assert_eq!(pragma("#pragma deprecated foo \\\nbar\n"), expected);
+    assert_eq!(pragma("#pragma deprecated foo \\\r\nbar\n"), expected);
+    assert_eq!(
+        pragma("#pragma \\\nnewdecls required"),
+        Some(("newdecls".to_string(), Some("required".to_string())))
+    );
+}
+
+#[test]
+fn as_pragma_none() {
+    assert_eq!(pragma("#pragma\n"), None);
+    assert_eq!(pragma("#define FOO"), None);
+}
+
+fn in_preprocessor(input: &str) -> Vec<(TokenKind, bool)> {
+    SourcepawnLexer::new(input)
+        .map(|symbol| (symbol.token_kind, symbol.in_preprocessor))
+        .collect()
+}
+
+#[test]
+fn pragma_crlf_continuation() {
+    let input = "#pragma deprecated foo \\\r\nbar\r\nint x;";
+
+    assert_eq!(
+        in_preprocessor(input),
+        vec![
+            (TokenKind::PreprocDir(PreprocDir::MPragma), true),
+            (TokenKind::Newline, false),
+            (TokenKind::Int, false),
+            (TokenKind::Identifier, false),
+            (TokenKind::Semicolon, false),
+            (TokenKind::Eof, false),
+        ]
+    );
+}
+
+#[test]
+fn pragma_crlf_termination() {
+    let input = "#pragma semicolon 1\r\nint x;";
+
+    assert_eq!(
+        in_preprocessor(input),
+        vec![
+            (TokenKind::PreprocDir(PreprocDir::MPragma), true),
+            (TokenKind::Newline, false),
+            (TokenKind::Int, false),
+            (TokenKind::Identifier, false),
+            (TokenKind::Semicolon, false),
+            (TokenKind::Eof, false),
+        ]
+    );
+}
+
+#[test]
+fn block_comment_crlf_continuation_in_define() {
+    let input = "#define FOO /* foo \\\r\nbar */ 1\r\nint x;";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+    assert_eq!(
+        symbols[2].token_kind,
+        TokenKind::Comment(Comment::BlockComment)
+    );
+    assert!(symbols[2].in_preprocessor);
+    assert!(symbols[3].in_preprocessor);
+    assert!(!symbols[4].in_preprocessor);
+}
+
+#[test]
+fn block_comment_crlf_in_define() {
+    let input = "#define FOO /* foo\r\nbar */ 1\r\nint x;";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+    assert_eq!(
+        symbols[2].token_kind,
+        TokenKind::Comment(Comment::BlockComment)
+    );
+    assert!(!symbols[2].in_preprocessor);
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "serde")]
+
+use sourcepawn_lexer::*;
+
+#[test]
+fn serialize_operator() {
+    let kind = TokenKind::Operator(Operator::Plus);
+
+    let json = serde_json::to_string(&kind).unwrap();
+    assert_eq!(json, r#""Operator(Plus)""#);
+    assert_eq!(serde_json::from_str::<TokenKind>(&json).unwrap(), kind);
+}
+
+#[test]
+fn serialize_unit_kind() {
+    let json = serde_json::to_string(&TokenKind::Identifier).unwrap();
+    assert_eq!(json, r#""Identifier""#);
+    assert_eq!(
+        serde_json::from_str::<TokenKind>(&json).unwrap(),
+        TokenKind::Identifier
+    );
+}
+
+#[test]
+fn kind_name_round_trip() {
+    let input = "#define FOO 1.0 // foo\nint x = 'a' >>>= \"b\"; /* bar */ @";
+
+    for symbol in SourcepawnLexer::new(input) {
+        let kind = symbol.token_kind;
+        assert_eq!(TokenKind::from_kind_name(&kind.kind_name()), Some(kind));
+    }
+    let kind = TokenKind::Error(LexError::EmptyChar);
+    assert_eq!(kind.kind_name(), "Error(EmptyChar)");
+    assert_eq!(TokenKind::from_kind_name(&kind.kind_name()), Some(kind));
+}
+
+#[test]
+fn deserialize_unknown_kind() {
+    for json in [
+        r#""Foo""#,
+        r#""Operator(Foo)""#,
+        r#""Foo(Plus)""#,
+        r#""Operator(Plus""#,
+    ] {
+        assert!(serde_json::from_str::<TokenKind>(json).is_err());
+    }
+}
+
+#[test]
+fn deserialize_legacy_map() {
+    let kind: TokenKind = serde_json::from_str(r#"{"Operator": "Plus"}"#).unwrap();
+    assert_eq!(kind, TokenKind::Operator(Operator::Plus));
+    let kind: TokenKind = serde_json::from_str(r#"{"Literal": "IntegerLiteral"}"#).unwrap();
+    assert_eq!(kind, TokenKind::Literal(Literal::IntegerLiteral));
+    assert!(serde_json::from_str::<TokenKind>(r#"{}"#).is_err());
+    assert!(serde_json::from_str::<TokenKind>(
+        r#"{"Operator": "Plus", "Literal": "IntegerLiteral"}"#
+    )
+    .is_err());
+}
+
+#[test]
+fn deserialize_former_operator_name() {
+    let expected = TokenKind::Operator(Operator::AssignUshr);
+
+    assert_eq!(
+        serde_json::from_str::<TokenKind>(r#"{"Operator": "AssignUshl"}"#).unwrap(),
+        expected
+    );
+    assert_eq!(
+        serde_json::from_str::<TokenKind>(r#""Operator(AssignUshl)""#).unwrap(),
+        expected
+    );
+    assert_eq!(expected.kind_name(), "Operator(AssignUshr)");
+}
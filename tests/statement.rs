@@ -0,0 +1,43 @@
+use sourcepawn_lexer::{is_for_clause_separator, SourcepawnLexer, Symbol, TokenKind};
+
+/// Returns the offsets of the semicolons and whether they are `for` clause separators.
+fn semicolons(input: &str) -> Vec<(u32, bool)> {
+    let symbols: Vec<Symbol> = SourcepawnLexer::new(input).collect();
+    symbols
+        .iter()
+        .enumerate()
+        .filter(|(_, symbol)| symbol.token_kind == TokenKind::Semicolon)
+        .map(|(idx, symbol)| {
+            (
+                symbol.range.start().into(),
+                is_for_clause_separator(&symbols[..=idx]),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn for_clause_separators() {
+    let input = "for (int i=0; i<n; i++) foo();";
+
+    assert_eq!(semicolons(input), vec![(12, true), (17, true), (29, false)]);
+}
+
+#[test]
+fn for_clause_separators_nested_parens() {
+    let input = "for (int i = (0); i < f(n); i++)\n{\n    x = (1);\n}";
+
+    assert_eq!(semicolons(input), vec![(16, true), (26, true), (46, false)]);
+}
+
+#[test]
+fn for_clause_separators_empty_header() {
+    assert_eq!(semicolons("for (;;) {}"), vec![(5, true), (6, true)]);
+}
+
+#[test]
+fn for_clause_separators_not_for() {
+    assert_eq!(semicolons("while (x) { y; }"), vec![(13, false)]);
+    assert_eq!(semicolons("foo(x);"), vec![(6, false)]);
+    assert_eq!(semicolons(";"), vec![(0, false)]);
+}
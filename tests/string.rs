@@ -0,0 +1,241 @@
+mod utils;
+
+use insta::assert_json_snapshot;
+use sourcepawn_lexer::*;
+use utils::collect_tokens;
+
+#[test]
+fn unterminated_string_newline() {
+    let input = r#""oops
+int x;"#;
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_json_snapshot!(collect_tokens(&mut lexer));
+}
+
+#[test]
+fn unterminated_string_carriage_return() {
+    let input = "\"oops\r\nint x;";
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_json_snapshot!(collect_tokens(&mut lexer));
+}
+
+#[test]
+fn char_carriage_return() {
+    let input = "c = '\r';\r\nint x;";
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_json_snapshot!(collect_tokens(&mut lexer));
+}
+
+#[test]
+fn unterminated_string_carriage_return_kind() {
+    let input = "\"oops\r\nint x;";
+
+    let symbol = SourcepawnLexer::new(input).next().unwrap();
+    assert_eq!(
+        symbol.token_kind,
+        TokenKind::Error(LexError::UnterminatedString)
+    );
+    assert_eq!(symbol.range, TextRange::new(0.into(), 5.into()));
+}
+
+#[test]
+fn unterminated_string_in_declaration() {
+    let input = "int x = \"unterminated\nint y;";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+    let errors: Vec<_> = symbols
+        .iter()
+        .filter(|symbol| symbol.token_kind == TokenKind::Error(LexError::UnterminatedString))
+        .collect();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(&input[errors[0].range], "\"unterminated");
+    assert_eq!(symbols[4].token_kind, TokenKind::Newline);
+    assert_eq!(symbols[5].token_kind, TokenKind::Int);
+}
+
+#[test]
+fn unterminated_string_at_eof() {
+    let input = "int x = \"unterminated";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+    assert_eq!(
+        symbols[3].token_kind,
+        TokenKind::Error(LexError::UnterminatedString)
+    );
+    assert_eq!(symbols[3].range, TextRange::new(8.into(), 21.into()));
+    assert_eq!(symbols[4].token_kind, TokenKind::Eof);
+}
+
+#[test]
+fn unescape_string() {
+    let symbol = SourcepawnLexer::new(r#""a\nb\x41;\"""#).next().unwrap();
+    assert_eq!(symbol.unescape('\\'), Some("a\nbA\"".to_string()));
+}
+
+#[test]
+fn ctrl_char_pragma() {
+    let input = r#"#pragma ctrlchar '%'
+"a%nb\n"
+#pragma ctrlchar
+"a%nb\n"
+"#;
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_eq!(lexer.ctrl_char(), '\\');
+    let mut decoded = vec![];
+    while let Some(symbol) = lexer.next() {
+        if symbol.token_kind == TokenKind::Literal(Literal::StringLiteral) {
+            decoded.push((lexer.ctrl_char(), symbol.unescape(lexer.ctrl_char())));
+        }
+    }
+    assert_eq!(
+        decoded,
+        vec![
+            ('%', Some("a\nb\\n".to_string())),
+            ('\\', Some("a%nb\n".to_string()))
+        ]
+    );
+}
+
+#[test]
+fn ctrl_char_pragma_numeric() {
+    let mut lexer = SourcepawnLexer::new("#pragma ctrlchar 94\n");
+    lexer.next();
+    assert_eq!(lexer.ctrl_char(), '^');
+}
+
+#[test]
+fn unterminated_string_trailing_backslash_at_eof() {
+    let input = r#""foo\"#;
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_json_snapshot!(collect_tokens(&mut lexer));
+    assert_eq!(
+        lexer.diagnostics(),
+        &[(
+            TextRange::new(4.into(), 5.into()),
+            LexError::IncompleteLineContinuation
+        )]
+    );
+}
+
+#[test]
+fn unterminated_string_trailing_backslash_inline_text() {
+    let symbol = SourcepawnLexer::new(r#"x = "foo\"#).nth(2).unwrap();
+    assert_eq!(
+        symbol.token_kind,
+        TokenKind::Error(LexError::UnterminatedString)
+    );
+    assert_eq!(symbol.inline_text(), r#""foo\"#);
+}
+
+#[test]
+fn string_line_continuation_not_at_eof() {
+    let mut lexer = SourcepawnLexer::new("\"foo\\\nbar\"");
+    assert_eq!(
+        lexer.next().unwrap().token_kind,
+        TokenKind::Literal(Literal::StringLiteral)
+    );
+    assert!(lexer.diagnostics().is_empty());
+}
+
+fn invalid_escapes(input: &str) -> Vec<(TextRange, LexError)> {
+    SourcepawnLexer::new(input)
+        .next()
+        .unwrap()
+        .validate_escapes('\\')
+}
+
+#[test]
+fn validate_escapes_unknown() {
+    assert_eq!(
+        invalid_escapes(r#""a\qb""#),
+        vec![(TextRange::new(2.into(), 4.into()), LexError::InvalidEscape)]
+    );
+}
+
+#[test]
+fn validate_escapes_hex_without_digits() {
+    assert_eq!(
+        invalid_escapes(r#""\xZZ""#),
+        vec![(TextRange::new(1.into(), 3.into()), LexError::InvalidEscape)]
+    );
+}
+
+#[test]
+fn validate_escapes_valid() {
+    assert!(invalid_escapes(r#""a\n\x41;\101\"\\\%""#).is_empty());
+    assert!(invalid_escapes(r"'\''").is_empty());
+    assert!(invalid_escapes("\"foo\\\nbar\"").is_empty());
+    assert!(invalid_escapes("foo").is_empty());
+}
+
+#[test]
+fn validate_escapes_several() {
+    assert_eq!(
+        invalid_escapes(r#""\q é\w""#),
+        vec![
+            (TextRange::new(1.into(), 3.into()), LexError::InvalidEscape),
+            (TextRange::new(6.into(), 8.into()), LexError::InvalidEscape)
+        ]
+    );
+}
+
+#[test]
+fn validate_escapes_trailing_backslash() {
+    assert_eq!(
+        invalid_escapes(r#""foo\"#),
+        vec![(TextRange::new(4.into(), 5.into()), LexError::InvalidEscape)]
+    );
+}
+
+#[test]
+fn validate_escapes_ctrl_char() {
+    let symbol = SourcepawnLexer::new(r#""^n\q""#).next().unwrap();
+    assert!(symbol.validate_escapes('^').is_empty());
+    assert_eq!(symbol.validate_escapes('\\').len(), 1);
+}
+
+fn string_value(input: &str) -> Option<String> {
+    SourcepawnLexer::new(input).next().unwrap().string_value()
+}
+
+#[test]
+fn string_value_escapes() {
+    assert_eq!(string_value(r#""a\nb""#), Some("a\nb".to_string()));
+    assert_eq!(
+        string_value(r#""quote: \"""#),
+        Some("quote: \"".to_string())
+    );
+    assert_eq!(
+        string_value(r#""\t\r\\\%\x41;\101""#),
+        Some("\t\r\\%AA".to_string())
+    );
+}
+
+#[test]
+fn string_value_line_continuation() {
+    assert_eq!(
+        string_value("\"foo \\\n bar\\\r\nbaz\""),
+        Some("foo  barbaz".to_string())
+    );
+}
+
+#[test]
+fn string_value_not_string() {
+    assert_eq!(string_value("'a'"), None);
+    assert_eq!(string_value("\"foo"), None);
+    assert_eq!(string_value(r#""\q""#), None);
+}
+
+#[test]
+fn triple_quote() {
+    // An empty char literal, followed by a lone quote which does not start a char literal.
+    let input = "'''";
+
+    let mut lexer = SourcepawnLexer::new(input);
+    assert_json_snapshot!(collect_tokens(&mut lexer));
+}
@@ -0,0 +1,210 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    hash::{Hash, Hasher},
+};
+
+use sourcepawn_lexer::*;
+
+fn hash(symbol: &Symbol) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    symbol.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn as_identifier_identifier() {
+    let symbol = SourcepawnLexer::new("foo").next().unwrap();
+    assert_eq!(symbol.as_identifier(), Some("foo"));
+}
+
+#[test]
+fn as_identifier_keyword() {
+    let symbol = SourcepawnLexer::new("int").next().unwrap();
+    assert_eq!(symbol.as_identifier(), None);
+}
+
+#[test]
+fn kind_is_any_of() {
+    let kinds = [
+        TokenKind::Semicolon,
+        TokenKind::Operator(Operator::Assign),
+        TokenKind::Identifier,
+    ];
+    let symbols: Vec<_> = SourcepawnLexer::new("foo = 1;").collect();
+    assert!(symbols[0].kind_is_any_of(&kinds));
+    assert!(symbols[1].kind_is_any_of(&kinds));
+    assert!(!symbols[2].kind_is_any_of(&kinds));
+    assert!(symbols[3].kind_is_any_of(&kinds));
+    assert!(!TokenKind::Operator(Operator::AssignAdd).matches_any(&kinds));
+}
+
+#[test]
+fn hash_equal_symbols() {
+    let input = "int foo; int foo;";
+
+    let symbols: Vec<_> = SourcepawnLexer::new(input).collect();
+    let range = symbols[0].range;
+    // Symbols built by hand must be equal to, and hash the same as, lexed ones.
+    let keyword = Symbol::new(TokenKind::Int, None, range, 0);
+    let keyword_with_text = Symbol::new(TokenKind::Int, Some("int"), range, 0);
+    assert_eq!(symbols[0], keyword);
+    assert_eq!(keyword, keyword_with_text);
+    assert_eq!(hash(&symbols[0]), hash(&keyword));
+    assert_eq!(hash(&keyword), hash(&keyword_with_text));
+
+    let identifier = Symbol::new(TokenKind::Identifier, Some("foo"), symbols[1].range, 1);
+    assert_eq!(symbols[1], identifier);
+    assert_eq!(hash(&symbols[1]), hash(&identifier));
+}
+
+#[test]
+fn hash_set_of_symbols() {
+    let input = "int foo;";
+
+    let set: HashSet<Symbol> = SourcepawnLexer::new(input)
+        .chain(SourcepawnLexer::new(input))
+        .collect();
+    assert_eq!(set.len(), SourcepawnLexer::new(input).count());
+}
+
+#[test]
+fn text_ref_identifier() {
+    let symbol = SourcepawnLexer::new("foo").next().unwrap();
+    assert_eq!(symbol.text_ref(), Some("foo"));
+    assert_eq!(symbol.text_ref().unwrap(), symbol.text());
+}
+
+#[test]
+fn text_ref_variable_kinds() {
+    let input = r#"#pragma semicolon 1
+"foo" 1.0 /* bar */"#;
+
+    for symbol in SourcepawnLexer::new(input) {
+        match symbol.token_kind.static_text() {
+            Some(text) => {
+                assert_eq!(symbol.text_ref(), None);
+                assert_eq!(symbol.text(), text);
+            }
+            None => assert_eq!(symbol.text_ref().unwrap(), symbol.text()),
+        }
+    }
+}
+
+#[test]
+fn text_ref_fixed_kinds() {
+    for symbol in SourcepawnLexer::new("int ; += #define") {
+        assert_eq!(symbol.text_ref(), None);
+        assert_eq!(
+            symbol.token_kind.static_text().unwrap(),
+            symbol.text().as_str()
+        );
+    }
+}
+
+#[test]
+fn is_assignment_target_start() {
+    let input = "foo this ( 1 int ; \"bar\"";
+
+    let targets: Vec<_> = SourcepawnLexer::new(input)
+        .without_eof()
+        .map(|symbol| {
+            (
+                symbol.text().to_string(),
+                symbol.is_assignment_target_start(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        targets,
+        vec![
+            ("foo".to_string(), true),
+            ("this".to_string(), true),
+            ("(".to_string(), true),
+            ("1".to_string(), false),
+            ("int".to_string(), false),
+            (";".to_string(), false),
+            ("\"bar\"".to_string(), false),
+        ]
+    );
+}
+
+#[test]
+fn slice_checked_same_source() {
+    let input = "int foo = \"bär\";";
+
+    for symbol in SourcepawnLexer::new(input) {
+        assert_eq!(symbol.slice_checked(input), Some(&input[symbol.range]));
+    }
+}
+
+#[test]
+fn slice_checked_out_of_bounds() {
+    let symbol = SourcepawnLexer::new("int foobar").nth(1).unwrap();
+    assert_eq!(symbol.slice_checked("int foo"), None);
+    assert_eq!(symbol.slice_checked(""), None);
+}
+
+#[test]
+fn slice_checked_char_boundary() {
+    let symbol = SourcepawnLexer::new("int foo").nth(1).unwrap();
+    // The range is 4..7, which ends inside the second `é` (bytes 6 and 7).
+    assert_eq!(symbol.slice_checked("int éé"), None);
+    // Here it starts inside the `é` (bytes 3 and 4).
+    assert_eq!(symbol.slice_checked("inté foo"), None);
+}
+
+#[test]
+fn canonical_fixed_kinds() {
+    let symbols: Vec<_> = SourcepawnLexer::new("if (x) += \r\n").collect();
+    let canonical: Vec<_> = symbols.iter().map(Symbol::canonical).collect();
+    assert_eq!(canonical, vec!["if", "(", "", ")", "+=", "\n", "\0"]);
+}
+
+#[test]
+fn canonical_variable_kinds() {
+    let symbols: Vec<_> = SourcepawnLexer::new("foo 1 \"bar\" // baz").collect();
+    assert!(symbols[..4]
+        .iter()
+        .all(|symbol| symbol.canonical().is_empty()));
+    assert_eq!(symbols[0].as_identifier(), Some("foo"));
+    assert_eq!(symbols[1].literal_text(), Some("1"));
+    assert_eq!(symbols[2].literal_text(), Some("\"bar\""));
+    assert_eq!(symbols[3].literal_text(), None);
+}
+
+fn variadic_markers(input: &str) -> Vec<bool> {
+    let symbols: Vec<_> = SourcepawnLexer::new(input)
+        .filter(|symbol| !symbol.token_kind.is_trivia())
+        .collect();
+    symbols
+        .iter()
+        .enumerate()
+        .filter(|(_, symbol)| symbol.token_kind == TokenKind::Operator(Operator::Ellipses))
+        .map(|(idx, symbol)| symbol.is_variadic_marker(idx.checked_sub(1).map(|idx| &symbols[idx])))
+        .collect()
+}
+
+#[test]
+fn is_variadic_marker_signature() {
+    assert_eq!(variadic_markers("f(int x, ...)"), vec![true]);
+    assert_eq!(variadic_markers("f(...)"), vec![true]);
+    assert_eq!(
+        variadic_markers("native void Format(char[] buffer, const char[] format, any ...);"),
+        vec![true]
+    );
+    assert_eq!(variadic_markers("f(int ...)"), vec![true]);
+    assert_eq!(variadic_markers("f(const char[] ...)"), vec![true]);
+}
+
+#[test]
+fn is_variadic_marker_misuse() {
+    assert_eq!(variadic_markers("x = ...;"), vec![false]);
+    assert_eq!(variadic_markers("... x"), vec![false]);
+    assert_eq!(variadic_markers("f(1 + ...)"), vec![false]);
+}
+
+#[test]
+fn is_variadic_marker_not_ellipses() {
+    let symbols: Vec<_> = SourcepawnLexer::new("f(x)").collect();
+    assert!(!symbols[2].is_variadic_marker(Some(&symbols[1])));
+}
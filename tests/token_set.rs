@@ -0,0 +1,70 @@
+use sourcepawn_lexer::{Comment, LexError, Literal, Operator, PreprocDir, TokenKind, TokenSet};
+
+#[test]
+fn token_set_contains() {
+    let set = TokenSet::new(&[
+        TokenKind::Identifier,
+        TokenKind::Eof,
+        TokenKind::Operator(Operator::Plus),
+        TokenKind::PreprocDir(PreprocDir::MDefine),
+    ]);
+    assert!(set.contains(TokenKind::Identifier));
+    assert!(set.contains(TokenKind::Eof));
+    assert!(set.contains(TokenKind::Operator(Operator::Plus)));
+    assert!(!set.contains(TokenKind::Operator(Operator::Minus)));
+    assert!(set.contains(TokenKind::PreprocDir(PreprocDir::MDefine)));
+    assert!(!set.contains(TokenKind::PreprocDir(PreprocDir::MUndef)));
+    assert!(!set.contains(TokenKind::Literal(Literal::IntegerLiteral)));
+    assert!(!set.contains(TokenKind::Semicolon));
+}
+
+#[test]
+fn token_set_payload_kinds_do_not_overlap() {
+    let kinds = [
+        TokenKind::Literal(Literal::IntegerLiteral),
+        TokenKind::Comment(Comment::LineComment),
+        TokenKind::Operator(Operator::Assign),
+        TokenKind::PreprocDir(PreprocDir::MIf),
+        TokenKind::Error(LexError::TokenTooLong),
+        TokenKind::Identifier,
+    ];
+    for (i, kind) in kinds.iter().enumerate() {
+        let set = TokenSet::new(&[*kind]);
+        for (j, other) in kinds.iter().enumerate() {
+            assert_eq!(set.contains(*other), i == j);
+        }
+    }
+}
+
+#[test]
+fn token_set_union() {
+    let set = TokenSet::TYPE_KEYWORDS.union(TokenSet::STATEMENT_START);
+    assert!(set.contains(TokenKind::Int));
+    assert!(set.contains(TokenKind::Void));
+    assert!(set.contains(TokenKind::Return));
+    assert!(set.contains(TokenKind::LBrace));
+    assert!(!set.contains(TokenKind::Identifier));
+    assert_eq!(TokenSet::EMPTY.union(set), set);
+}
+
+#[test]
+fn token_set_insert() {
+    let mut set = TokenSet::EMPTY;
+    assert!(set.is_empty());
+    set.insert(TokenKind::Comma);
+    set.insert(TokenKind::Operator(Operator::Tilde));
+    assert!(!set.is_empty());
+    assert_eq!(
+        set,
+        TokenSet::new(&[TokenKind::Operator(Operator::Tilde), TokenKind::Comma])
+    );
+}
+
+#[test]
+fn token_set_const() {
+    const SET: TokenSet = TokenSet::EMPTY
+        .with(TokenKind::If)
+        .union(TokenSet::TYPE_KEYWORDS);
+    assert!(SET.contains(TokenKind::If));
+    assert!(SET.contains(TokenKind::Int));
+}
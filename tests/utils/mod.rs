@@ -1,28 +1,37 @@
 #[allow(unused_imports)]
 use serde::{Deserialize, Serialize};
-use sourcepawn_lexer::SourcepawnLexer;
+use sourcepawn_lexer::{SourcepawnLexer, TokenKind};
 
 #[cfg_attr(test, derive(Serialize, Deserialize))]
 pub struct Output {
-    pub kind: crate::TokenKind,
+    pub kind: String,
     pub text: String,
     pub range_start: u32,
     pub range_end: u32,
     pub delta: crate::Delta,
-    pub in_preprocessor: bool
+    pub in_preprocessor: bool,
 }
 
+#[allow(dead_code)]
 pub fn collect_tokens(lexer: &mut SourcepawnLexer) -> Vec<Output> {
     let mut res = Vec::new();
-    while let Some(symbol) = lexer.next() {
+    for symbol in lexer.by_ref() {
         res.push(Output {
-            kind: symbol.token_kind,
+            kind: symbol.token_kind.kind_name().to_string(),
             text: symbol.text().to_string(),
             range_start: symbol.range.start().into(),
             range_end: symbol.range.end().into(),
             delta: symbol.delta,
-            in_preprocessor: lexer.in_preprocessor()
+            in_preprocessor: symbol.in_preprocessor,
         });
     }
     res
-}
\ No newline at end of file
+}
+
+#[allow(dead_code)]
+pub fn kinds(input: &str) -> Vec<TokenKind> {
+    SourcepawnLexer::new(input)
+        .without_eof()
+        .map(|symbol| symbol.token_kind)
+        .collect()
+}